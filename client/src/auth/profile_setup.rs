@@ -1,47 +1,47 @@
 use dioxus::prelude::*;
+use utils::validation::{Field, validate_nickname, validate_username};
 
-use crate::{backend::setup_user, components::CenteredForm};
+use crate::{
+    backend::{setup_user, validation_error},
+    components::CenteredForm,
+};
 
 #[component]
 pub fn AuthProfileSetup() -> Element {
     let mut error = use_signal(|| None as Option<String>);
+    let mut username_error = use_signal(|| None as Option<String>);
+    let mut nickname_error = use_signal(|| None as Option<String>);
 
     let onsubmit = move |e: Event<FormData>| {
         e.prevent_default();
 
         spawn(async move {
             let data = e.data();
-
-            let username = match data.get_first("username") {
-                Some(v) => match v {
-                    FormValue::Text(s) if !s.trim().is_empty() => s.trim().to_string(),
-                    _ => {
-                        error.set(Some("Invalid username".to_string()));
-                        return;
-                    }
-                },
-                None => {
-                    error.set(Some("Username is required".to_string()));
-                    return;
-                }
+            let field = |name: &str| match data.get_first(name) {
+                Some(FormValue::Text(s)) => s,
+                _ => String::new(),
             };
 
-            let nickname = match data.get_first("nickname") {
-                Some(v) => match v {
-                    FormValue::Text(s) if !s.trim().is_empty() => s.trim().to_string(),
-                    _ => {
-                        error.set(Some("Invalid username".to_string()));
-                        return;
-                    }
-                },
-                None => {
-                    error.set(Some("Username is required".to_string()));
-                    return;
-                }
+            let username = validate_username(&field("username"));
+            let nickname = validate_nickname(&field("nickname"));
+            username_error.set(username.as_ref().err().map(|e| e.to_string()));
+            nickname_error.set(nickname.as_ref().err().map(|e| e.to_string()));
+            error.set(None);
+
+            let (Ok(username), Ok(nickname)) = (username, nickname) else {
+                return;
             };
 
             if let Err(e) = setup_user(username, nickname).await {
-                error.set(Some(format!("Failed to set up user: {}", e)));
+                match validation_error(&e) {
+                    Some(invalid) if invalid.field == Field::Username => {
+                        username_error.set(Some(invalid.to_string()))
+                    }
+                    Some(invalid) if invalid.field == Field::Nickname => {
+                        nickname_error.set(Some(invalid.to_string()))
+                    }
+                    _ => error.set(Some(format!("Failed to set up user: {}", e))),
+                }
                 return;
             }
 
@@ -67,6 +67,10 @@ pub fn AuthProfileSetup() -> Element {
                     required: true,
                 }
 
+                { if let Some(err) = username_error() { rsx! {
+                    p { class: "text-xs text-red-700 -mt-3 mb-4", "{err}" }
+                } } else { rsx! {} } }
+
                 input {
                     r#type: "text",
                     name: "nickname",
@@ -75,6 +79,10 @@ pub fn AuthProfileSetup() -> Element {
                     required: true,
                 }
 
+                { if let Some(err) = nickname_error() { rsx! {
+                    p { class: "text-xs text-red-700 -mt-3 mb-4", "{err}" }
+                } } else { rsx! {} } }
+
                 {
                     match error() {
                         Some(err) => rsx! {
@@ -1,5 +1,9 @@
 use dioxus::prelude::*;
 use std::future::Future;
+use utils::{
+    errors::{ApiError, AuthReason},
+    validation::ValidationError,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum ApiData<T> {
@@ -55,3 +59,17 @@ where
 
     data
 }
+
+pub fn validation_error(err: &anyhow::Error) -> Option<&ValidationError> {
+    match err.downcast_ref::<ApiError>()? {
+        ApiError::Validation(e) => Some(e),
+        _ => None,
+    }
+}
+
+pub fn auth_error(err: &anyhow::Error) -> Option<AuthReason> {
+    match err.downcast_ref::<ApiError>()? {
+        ApiError::Unauthorized { reason } => Some(*reason),
+        _ => None,
+    }
+}
@@ -0,0 +1,35 @@
+use dioxus::prelude::*;
+use utils::{LogError, data::ContactInfo};
+use uuid::Uuid;
+
+use super::{ApiData, list_contacts, remove_contact, save_contact};
+
+pub static CONTACTS: GlobalSignal<ApiData<Vec<ContactInfo>>> = GlobalSignal::new(ApiData::loading);
+
+pub async fn reload_contacts() {
+    match list_contacts().await {
+        Ok(contacts) => *CONTACTS.write() = ApiData::Loaded(contacts),
+        Err(err) => *CONTACTS.write() = ApiData::error(err),
+    }
+}
+
+pub fn find_contact(user_uuid: Uuid) -> Option<ContactInfo> {
+    CONTACTS
+        .read()
+        .as_ref()?
+        .iter()
+        .find(|contact| contact.user.uuid == user_uuid)
+        .cloned()
+}
+
+pub async fn set_contact(user_uuid: Uuid, nickname: Option<String>) {
+    if save_contact(user_uuid, nickname).await.log_error().is_ok() {
+        reload_contacts().await;
+    }
+}
+
+pub async fn unset_contact(user_uuid: Uuid) {
+    if remove_contact(user_uuid).await.log_error().is_ok() {
+        reload_contacts().await;
+    }
+}
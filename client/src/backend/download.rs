@@ -0,0 +1,37 @@
+use anyhow::{Result, anyhow};
+
+#[cfg(feature = "web")]
+pub fn save_file(name: &str, mime: &str, bytes: &[u8]) -> Result<()> {
+    use web_sys::{
+        Blob, BlobPropertyBag, HtmlAnchorElement, Url,
+        js_sys::{Array, Uint8Array},
+        wasm_bindgen::JsCast,
+    };
+
+    let parts = Array::new();
+    parts.push(&Uint8Array::from(bytes));
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+        .map_err(|e| anyhow!("Failed to create blob: {:?}", e))?;
+    let url = Url::create_object_url_with_blob(&blob)
+        .map_err(|e| anyhow!("Failed to create object URL: {:?}", e))?;
+
+    let anchor: HtmlAnchorElement = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| anyhow!("Document is not available"))?
+        .create_element("a")
+        .map_err(|e| anyhow!("Failed to create anchor: {:?}", e))?
+        .dyn_into()
+        .map_err(|e| anyhow!("Failed to cast anchor: {:?}", e))?;
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+
+    Url::revoke_object_url(&url).map_err(|e| anyhow!("Failed to revoke object URL: {:?}", e))
+}
+
+#[cfg(not(feature = "web"))]
+pub fn save_file(name: &str, _mime: &str, bytes: &[u8]) -> Result<()> {
+    std::fs::write(name, bytes).map_err(|e| anyhow!("Failed to save {}: {}", name, e))
+}
@@ -1,11 +1,11 @@
 use anyhow::{Result, anyhow, bail};
-use chrono::{NaiveDateTime, Utc};
+use chrono::{Duration, NaiveDateTime, Utc};
 use dioxus::prelude::*;
 use serde::de::DeserializeOwned;
 use std::sync::{LazyLock, Mutex};
 use uuid::Uuid;
 
-use crate::components::logout;
+use crate::{backend::auth_error, components::logout};
 use utils::{
     auth::KratosUserData,
     config::{
@@ -15,11 +15,19 @@ use utils::{
         },
         on_api_base_url, on_auth_base_url,
     },
-    data::{ChatInfo, MessageInfo, UserInfo},
+    data::{
+        AccessTokenInfo, ChatFolder, ChatInfo, ChatKind, ChatPreferences, ContactInfo,
+        FolderFilters, MessageInfo, Presence, PrivacySettings, TokenScope, UserInfo, UserStatus,
+    },
+    errors::{ApiError, AuthReason},
     requests::*,
 };
 
-static JWT: LazyLock<Mutex<Option<(String, NaiveDateTime)>>> = LazyLock::new(|| Mutex::new(None));
+const JWT_REFRESH_MARGIN_SECONDS: i64 = 60;
+
+static JWT: LazyLock<Mutex<Option<SessionTokensResponse>>> = LazyLock::new(|| Mutex::new(None));
+static JWT_REFRESH: LazyLock<futures::lock::Mutex<()>> =
+    LazyLock::new(|| futures::lock::Mutex::new(()));
 pub static CENTRIFUGO_JWT: LazyLock<Mutex<Option<(String, NaiveDateTime)>>> =
     LazyLock::new(|| Mutex::new(None));
 
@@ -29,7 +37,7 @@ async fn regenerate_centrifugo_jwt() {
             *CENTRIFUGO_JWT.lock().unwrap() = Some(token);
         }
         Err(e) => {
-            if e.to_string().contains("User not found") {
+            if auth_error(&e) == Some(AuthReason::UserNotFound) {
                 logout().await;
             }
             error!("Failed to request centrifugo JWT: {}", e);
@@ -69,7 +77,7 @@ pub struct Request {
     pub url: String,
     pub method: Method,
     pub headers: Vec<(String, String)>,
-    pub body: Option<String>,
+    pub body: Option<Vec<u8>>,
 }
 
 impl Request {
@@ -91,11 +99,16 @@ impl Request {
         }
     }
 
-    #[cfg(feature = "web")]
     pub async fn send_decode<T>(self) -> Result<T>
     where
         T: DeserializeOwned + Clone,
     {
+        let bytes = self.send_bytes().await?;
+        serde_json::from_slice(&bytes).map_err(|e| anyhow!(e.to_string()))
+    }
+
+    #[cfg(feature = "web")]
+    pub async fn send_bytes(self) -> Result<Vec<u8>> {
         use gloo_net::http::Request as GlooRequest;
 
         let mut request = match self.method {
@@ -108,25 +121,26 @@ impl Request {
             .iter()
             .fold(request, |req, (k, v)| req.header(k, v));
         let request = if let Some(body) = self.body {
-            request.body(body)
+            request.body(web_sys::js_sys::Uint8Array::from(body.as_slice()))
         } else {
             request.build()
         }?;
         let response = request.send().await?;
 
         if !response.ok() {
-            bail!("Request failed with status: {}", response.status())
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            if let Ok(api_error) = serde_json::from_str::<ApiError>(&text) {
+                return Err(api_error.into());
+            }
+            bail!("Request failed with status: {}", status)
         }
 
-        let text = response.text().await?;
-        serde_json::from_str(&text).map_err(|e| anyhow!(e.to_string()))
+        Ok(response.binary().await?)
     }
 
     #[cfg(not(feature = "web"))]
-    pub async fn send_decode<T>(self) -> Result<T>
-    where
-        T: DeserializeOwned + Clone,
-    {
+    pub async fn send_bytes(self) -> Result<Vec<u8>> {
         use reqwest::Client;
 
         let client = Client::new();
@@ -145,11 +159,16 @@ impl Request {
         };
         let response = request.send().await.map_err(|e| anyhow!(e.to_string()))?;
         if !response.status().is_success() {
-            bail!("Request failed with status: {}", response.status())
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            if let Ok(api_error) = serde_json::from_str::<ApiError>(&text) {
+                return Err(api_error.into());
+            }
+            bail!("Request failed with status: {}", status)
         }
 
-        let text = response.text().await.map_err(|e| anyhow!(e.to_string()))?;
-        serde_json::from_str(&text).map_err(|e| anyhow!(e.to_string()))
+        let bytes = response.bytes().await.map_err(|e| anyhow!(e.to_string()))?;
+        Ok(bytes.to_vec())
     }
 }
 
@@ -157,7 +176,7 @@ pub struct RequestBuilder {
     pub url: String,
     pub method: Method,
     pub headers: Vec<(String, String)>,
-    pub body: Option<String>,
+    pub body: Option<Vec<u8>>,
 }
 
 impl RequestBuilder {
@@ -167,17 +186,43 @@ impl RequestBuilder {
     }
 
     pub fn add_body_from_json<T: serde::Serialize>(mut self, body: &T) -> Self {
-        self.body = Some(serde_json::to_string(body).unwrap());
+        self.body = Some(serde_json::to_vec(body).unwrap());
         self.add_header("Content-Type", "application/json")
     }
 
+    pub fn add_body_bytes(mut self, body: Vec<u8>, content_type: &str) -> Self {
+        self.body = Some(body);
+        self.add_header("Content-Type", content_type)
+    }
+
     async fn regenerate_jwt() {
+        let refresh_token = {
+            let guard = JWT.lock().unwrap();
+            guard
+                .as_ref()
+                .filter(|session| session.refresh_expires_at > Utc::now().naive_utc())
+                .map(|session| session.refresh_token.clone())
+        };
+
+        if let Some(refresh_token) = refresh_token {
+            match refresh_jwt(refresh_token).await {
+                Ok(session) => {
+                    *JWT.lock().unwrap() = Some(session);
+                    return;
+                }
+                Err(e) => {
+                    warn!("Failed to refresh JWT, requesting a new session: {}", e);
+                }
+            }
+        }
+
         match generate_jwt().await {
-            Ok(token) => {
-                *JWT.lock().unwrap() = Some(token);
+            Ok(session) => {
+                *JWT.lock().unwrap() = Some(session);
             }
             Err(e) => {
-                if e.to_string().contains("User not found") {
+                *JWT.lock().unwrap() = None;
+                if auth_error(&e) == Some(AuthReason::UserNotFound) {
                     logout().await;
                 }
                 error!("Failed to request JWT: {}", e);
@@ -185,24 +230,30 @@ impl RequestBuilder {
         }
     }
 
+    fn jwt_needs_refresh() -> bool {
+        let refresh_at = Utc::now().naive_utc() + Duration::seconds(JWT_REFRESH_MARGIN_SECONDS);
+        match JWT.lock().unwrap().as_ref() {
+            None => true,
+            Some(session) => session.expires_at <= refresh_at,
+        }
+    }
+
     pub async fn add_jwt(self) -> Self {
-        {
-            let jwt = {
-                let guard = JWT.lock().unwrap();
-                guard.clone()
-            };
-            match jwt {
-                None => Self::regenerate_jwt().await,
-                Some((_, expires_at)) if expires_at <= Utc::now().naive_utc() => {
-                    Self::regenerate_jwt().await
-                }
-                _ => {}
+        if Self::jwt_needs_refresh() {
+            let _refreshing = JWT_REFRESH.lock().await;
+            if Self::jwt_needs_refresh() {
+                Self::regenerate_jwt().await;
             }
-        };
+        }
 
-        if let Some(token) = JWT.lock().unwrap().as_ref() {
+        let jwt = JWT
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|session| session.jwt.clone());
+        if let Some(jwt) = jwt {
             let mut headers = self.headers.clone();
-            headers.push(("Authorization".to_string(), format!("Bearer {}", token.0)));
+            headers.push(("Authorization".to_string(), format!("Bearer {}", jwt)));
             return Self {
                 url: self.url,
                 method: self.method,
@@ -226,12 +277,37 @@ impl RequestBuilder {
     }
 }
 
-pub async fn generate_jwt() -> Result<(String, NaiveDateTime)> {
-    let response = Request::get(&on_api_base_url(jwt::IG_GENERATE).await)
+pub async fn generate_jwt() -> Result<SessionTokensResponse> {
+    Request::get(&on_api_base_url(jwt::IG_GENERATE).await)
         .build()
-        .send_decode::<GenerateJwtResponse>()
+        .send_decode::<SessionTokensResponse>()
+        .await
+}
+
+pub async fn refresh_jwt(refresh_token: String) -> Result<SessionTokensResponse> {
+    let request = RefreshJwtRequest { refresh_token };
+    Request::post(&on_api_base_url(jwt::IP_REFRESH).await)
+        .add_body_from_json(&request)
+        .build()
+        .send_decode::<SessionTokensResponse>()
+        .await
+}
+
+pub async fn revoke_session() -> Result<()> {
+    *CENTRIFUGO_JWT.lock().unwrap() = None;
+    let Some(session) = JWT.lock().unwrap().take() else {
+        return Ok(());
+    };
+
+    let request = RevokeSessionRequest {
+        refresh_token: session.refresh_token,
+    };
+    Request::post(&on_api_base_url(jwt::IP_REVOKE).await)
+        .add_body_from_json(&request)
+        .build()
+        .send_decode::<RevokeSessionResponse>()
         .await?;
-    Ok((response.jwt, response.expires_at))
+    Ok(())
 }
 
 pub async fn generate_centrifugo_jwt() -> Result<(String, NaiveDateTime)> {
@@ -244,6 +320,20 @@ pub async fn generate_centrifugo_jwt() -> Result<(String, NaiveDateTime)> {
     Ok((response.jwt, response.expires_at))
 }
 
+pub async fn generate_centrifugo_subscription_jwt(
+    channel: String,
+) -> Result<(String, NaiveDateTime)> {
+    let request = GenerateCentrifugoSubscriptionRequest { channel };
+    let response = Request::post(&on_api_base_url(jwt::IP_GENERATE_CENTRIFUGO_SUBSCRIPTION).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<GenerateJwtResponse>()
+        .await?;
+    Ok((response.jwt, response.expires_at))
+}
+
 pub async fn list_chats() -> Result<Vec<ChatInfo>> {
     let response = Request::get(&on_api_base_url(chats::IG_LIST).await)
         .add_jwt()
@@ -280,8 +370,110 @@ pub async fn verify_private_chat(user_uuid: Uuid) -> Result<Uuid> {
     Ok(response.0)
 }
 
-pub async fn new_group(title: String, members: Vec<Uuid>) -> Result<Uuid> {
-    let request = NewGroupRequest { title, members };
+pub async fn set_chat_preferences(chat_uuid: Uuid, preferences: ChatPreferences) -> Result<()> {
+    let request = SetChatPreferencesRequest {
+        chat_uuid,
+        preferences,
+    };
+    Request::post(&on_api_base_url(chats::IP_PREFERENCES).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<SetChatPreferencesResponse>()
+        .await?;
+    Ok(())
+}
+
+pub async fn list_contacts() -> Result<Vec<ContactInfo>> {
+    let response = Request::get(&on_api_base_url(contacts::IG_LIST).await)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<ListContactsResponse>()
+        .await?;
+    Ok(response.0)
+}
+
+pub async fn save_contact(user_uuid: Uuid, nickname: Option<String>) -> Result<ContactInfo> {
+    let request = SaveContactRequest {
+        user_uuid,
+        nickname,
+    };
+    let response = Request::post(&on_api_base_url(contacts::IP_SAVE).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<SaveContactResponse>()
+        .await?;
+    Ok(response.0)
+}
+
+pub async fn remove_contact(user_uuid: Uuid) -> Result<()> {
+    let request = RemoveContactRequest(user_uuid);
+    let _ = Request::post(&on_api_base_url(contacts::IP_REMOVE).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<RemoveContactResponse>()
+        .await?;
+    Ok(())
+}
+
+pub async fn list_folders() -> Result<Vec<ChatFolder>> {
+    let response = Request::get(&on_api_base_url(folders::IG_LIST).await)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<ListFoldersResponse>()
+        .await?;
+    Ok(response.0)
+}
+
+pub async fn save_folder(
+    uuid: Option<Uuid>,
+    name: String,
+    include_all: bool,
+    include: Vec<Uuid>,
+    filters: FolderFilters,
+) -> Result<ChatFolder> {
+    let request = SaveFolderRequest {
+        uuid,
+        name,
+        include_all,
+        include,
+        filters,
+    };
+    let response = Request::post(&on_api_base_url(folders::IP_SAVE).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<SaveFolderResponse>()
+        .await?;
+    Ok(response.0)
+}
+
+pub async fn delete_folder(uuid: Uuid) -> Result<()> {
+    let request = DeleteFolderRequest(uuid);
+    let _ = Request::post(&on_api_base_url(folders::IP_DELETE).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<DeleteFolderResponse>()
+        .await?;
+    Ok(())
+}
+
+pub async fn new_group(title: String, kind: ChatKind, members: Vec<Uuid>) -> Result<Uuid> {
+    let request = NewGroupRequest {
+        title,
+        kind,
+        members,
+    };
     let response = Request::post(&on_api_base_url(groups::IP_NEW).await)
         .add_body_from_json(&request)
         .add_jwt()
@@ -292,6 +484,18 @@ pub async fn new_group(title: String, members: Vec<Uuid>) -> Result<Uuid> {
     Ok(response.0)
 }
 
+pub async fn set_slow_mode(chat_uuid: Uuid, seconds: Option<u32>) -> Result<()> {
+    let request = SetSlowModeRequest { chat_uuid, seconds };
+    let _ = Request::post(&on_api_base_url(groups::IP_SLOW_MODE).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<SetSlowModeResponse>()
+        .await?;
+    Ok(())
+}
+
 pub async fn list_messages(chat_uuid: Uuid) -> Result<Vec<MessageInfo>> {
     let request = ListMessagesRequest(chat_uuid);
     let response = Request::post(&on_api_base_url(messages::IP_LIST).await)
@@ -344,6 +548,21 @@ pub async fn edit_message(uuid: Uuid, new_content: String) -> Result<()> {
     Ok(())
 }
 
+pub async fn report_message(message_uuid: Uuid, reason: String) -> Result<()> {
+    let request = ReportMessageRequest {
+        message_uuid,
+        reason,
+    };
+    Request::post(&on_api_base_url(messages::IP_REPORT).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<ReportMessageResponse>()
+        .await?;
+    Ok(())
+}
+
 pub async fn check_user() -> Result<bool> {
     let response = Request::get(&on_api_base_url(users::IG_CHECK).await)
         .build()
@@ -362,6 +581,18 @@ pub async fn my_user() -> Result<UserInfo> {
     Ok(response.0)
 }
 
+pub async fn get_user(uuid: Uuid) -> Result<UserInfo> {
+    let request = GetUserRequest(uuid);
+    let response = Request::post(&on_api_base_url(users::IP_GET).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<GetUserResponse>()
+        .await?;
+    Ok(response.0)
+}
+
 pub async fn get_username(username: String) -> Result<UserInfo> {
     let request = GetUsernameRequest(username);
     let response = Request::post(&on_api_base_url(users::IP_NAME).await)
@@ -384,14 +615,184 @@ pub async fn setup_user(username: String, nickname: String) -> Result<()> {
     Ok(())
 }
 
-pub async fn list_users(exclude_self: bool) -> Result<Vec<UserInfo>> {
-    let request = ListUsersRequest { exclude_self };
-    let response = Request::post(&on_api_base_url(users::IP_LIST).await)
+pub async fn update_profile(username: String, nickname: String) -> Result<UserInfo> {
+    let request = UpdateProfileRequest { username, nickname };
+    let response = Request::post(&on_api_base_url(users::IP_UPDATE).await)
         .add_body_from_json(&request)
         .add_jwt()
         .await
         .build()
-        .send_decode::<ListUsersResponse>()
+        .send_decode::<UpdateProfileResponse>()
+        .await?;
+    Ok(response.0)
+}
+
+pub async fn set_status(bio: String, status: Option<UserStatus>) -> Result<UserInfo> {
+    let request = SetStatusRequest { bio, status };
+    let response = Request::post(&on_api_base_url(users::IP_STATUS).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<SetStatusResponse>()
+        .await?;
+    Ok(response.0)
+}
+
+pub async fn get_privacy() -> Result<PrivacySettings> {
+    let response = Request::get(&on_api_base_url(users::IG_PRIVACY).await)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<GetPrivacyResponse>()
+        .await?;
+    Ok(response.0)
+}
+
+pub async fn save_privacy(settings: PrivacySettings) -> Result<PrivacySettings> {
+    let request = SavePrivacyRequest(settings);
+    let response = Request::post(&on_api_base_url(users::IP_PRIVACY_SAVE).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<SavePrivacyResponse>()
+        .await?;
+    Ok(response.0)
+}
+
+pub async fn list_access_tokens() -> Result<Vec<AccessTokenInfo>> {
+    let response = Request::get(&on_api_base_url(access_tokens::IG_LIST).await)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<ListAccessTokensResponse>()
+        .await?;
+    Ok(response.0)
+}
+
+pub async fn create_access_token(
+    name: String,
+    scopes: Vec<TokenScope>,
+    expires_at: Option<NaiveDateTime>,
+) -> Result<CreateAccessTokenResponse> {
+    let request = CreateAccessTokenRequest {
+        name,
+        scopes,
+        expires_at,
+    };
+    Request::post(&on_api_base_url(access_tokens::IP_CREATE).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<CreateAccessTokenResponse>()
+        .await
+}
+
+pub async fn revoke_access_token(uuid: Uuid) -> Result<()> {
+    let request = RevokeAccessTokenRequest(uuid);
+    let _ = Request::post(&on_api_base_url(access_tokens::IP_REVOKE).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<RevokeAccessTokenResponse>()
+        .await?;
+    Ok(())
+}
+
+pub async fn export_data() -> Result<Vec<u8>> {
+    Request::get(&on_api_base_url(users::IG_EXPORT).await)
+        .add_jwt()
+        .await
+        .build()
+        .send_bytes()
+        .await
+}
+
+pub async fn delete_account(confirm_username: String) -> Result<()> {
+    let request = DeleteAccountRequest { confirm_username };
+    let _ = Request::post(&on_api_base_url(users::IP_DELETE).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<DeleteAccountResponse>()
+        .await?;
+    Ok(())
+}
+
+pub async fn upload_avatar(bytes: Vec<u8>, content_type: String) -> Result<UserInfo> {
+    let response = Request::post(&on_api_base_url(avatars::IP_UPLOAD).await)
+        .add_body_bytes(bytes, &content_type)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<UploadAvatarResponse>()
+        .await?;
+    Ok(response.0)
+}
+
+pub async fn remove_avatar() -> Result<UserInfo> {
+    let response = Request::post(&on_api_base_url(avatars::IP_REMOVE).await)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<RemoveAvatarResponse>()
+        .await?;
+    Ok(response.0)
+}
+
+pub async fn avatar_url(user_uuid: Uuid, avatar_version: Option<i64>, size: u32) -> String {
+    format!(
+        "{}?size={}&v={}",
+        on_api_base_url(avatars::IG_GET)
+            .await
+            .replace("{user_uuid}", &user_uuid.to_string()),
+        size,
+        avatar_version.unwrap_or(0)
+    )
+}
+
+pub async fn search_users(
+    query: String,
+    limit: u64,
+    cursor: Option<u64>,
+) -> Result<SearchUsersResponse> {
+    let request = SearchUsersRequest {
+        query,
+        limit,
+        cursor,
+    };
+    let response = Request::post(&on_api_base_url(users::IP_SEARCH).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<SearchUsersResponse>()
+        .await?;
+    Ok(response)
+}
+
+pub async fn heartbeat() -> Result<()> {
+    let _ = Request::post(&on_api_base_url(presence::IP_HEARTBEAT).await)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<HeartbeatResponse>()
+        .await?;
+    Ok(())
+}
+
+pub async fn get_presence(user_uuids: Vec<Uuid>) -> Result<Vec<Presence>> {
+    let request = GetPresenceRequest(user_uuids);
+    let response = Request::post(&on_api_base_url(presence::IP_GET).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<GetPresenceResponse>()
         .await?;
     Ok(response.0)
 }
@@ -408,6 +809,40 @@ pub async fn chat_users(chat_uuid: Uuid) -> Result<Vec<UserInfo>> {
     Ok(response.0)
 }
 
+pub async fn list_blocked_users() -> Result<Vec<UserInfo>> {
+    let response = Request::get(&on_api_base_url(users::IG_BLOCKED).await)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<ListUsersResponse>()
+        .await?;
+    Ok(response.0)
+}
+
+pub async fn block_user(user_uuid: Uuid) -> Result<()> {
+    let request = BlockUserRequest(user_uuid);
+    let _ = Request::post(&on_api_base_url(users::IP_BLOCK).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<BlockUserResponse>()
+        .await?;
+    Ok(())
+}
+
+pub async fn unblock_user(user_uuid: Uuid) -> Result<()> {
+    let request = UnblockUserRequest(user_uuid);
+    let _ = Request::post(&on_api_base_url(users::IP_UNBLOCK).await)
+        .add_body_from_json(&request)
+        .add_jwt()
+        .await
+        .build()
+        .send_decode::<UnblockUserResponse>()
+        .await?;
+    Ok(())
+}
+
 async fn try_get_kratos_user() -> Result<KratosUserData> {
     Request::get(&on_auth_base_url(URI_WHOAMI).await)
         .build()
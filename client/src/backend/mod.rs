@@ -1,5 +1,11 @@
 mod api_data;
+mod contacts;
+mod download;
 mod endpoints;
+mod user_search;
 
 pub use api_data::*;
+pub use contacts::*;
+pub use download::*;
 pub use endpoints::*;
+pub use user_search::*;
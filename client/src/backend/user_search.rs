@@ -0,0 +1,85 @@
+use dioxus::prelude::*;
+use utils::{LogError, data::UserInfo};
+
+use super::{ApiData, search_users};
+
+const PAGE_SIZE: u64 = 20;
+const DEBOUNCE_MS: u32 = 250;
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct UserSearch {
+    pub query: Signal<String>,
+    pub results: Signal<ApiData<Vec<UserInfo>>>,
+    next_cursor: Signal<Option<u64>>,
+}
+
+impl UserSearch {
+    pub fn has_more(&self) -> bool {
+        self.next_cursor.read().is_some()
+    }
+
+    pub fn load_more(&self) {
+        let Some(cursor) = *self.next_cursor.peek() else {
+            return;
+        };
+
+        let query = self.query.peek().clone();
+        let mut results = self.results;
+        let mut next_cursor = self.next_cursor;
+        next_cursor.set(None);
+
+        spawn(async move {
+            if let Ok(page) = search_users(query, PAGE_SIZE, Some(cursor))
+                .await
+                .log_error()
+                && let ApiData::Loaded(users) = &mut *results.write()
+            {
+                users.extend(page.users);
+                next_cursor.set(page.next_cursor);
+            }
+        });
+    }
+}
+
+pub fn use_user_search() -> UserSearch {
+    let query = use_signal(String::new);
+    let mut results = use_signal(|| ApiData::Loaded(Vec::new()));
+    let mut next_cursor = use_signal(|| None);
+
+    use_effect(move || {
+        let current = query.read().trim().to_string();
+        next_cursor.set(None);
+
+        if current.is_empty() {
+            results.set(ApiData::Loaded(Vec::new()));
+            return;
+        }
+
+        results.set(ApiData::Loading);
+        spawn(async move {
+            gloo_timers::future::TimeoutFuture::new(DEBOUNCE_MS).await;
+            if query.peek().trim() != current {
+                return;
+            }
+
+            let page = search_users(current.clone(), PAGE_SIZE, None).await;
+            if query.peek().trim() != current {
+                return;
+            }
+
+            match page {
+                Ok(page) => {
+                    results.set(ApiData::Loaded(page.users));
+                    next_cursor.set(page.next_cursor);
+                }
+                Err(err) => results.set(ApiData::error(err)),
+            }
+        });
+    });
+
+    UserSearch {
+        query,
+        results,
+        next_cursor,
+    }
+}
@@ -1,11 +1,16 @@
+use chrono::Utc;
 use dioxus::prelude::*;
-use futures::{SinkExt, StreamExt, lock::Mutex};
+use futures::{SinkExt, StreamExt, lock::Mutex, stream::SplitSink};
 use serde_json::Value;
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
 
 use gloo_net::websocket::{Message, futures::WebSocket};
 
-use crate::backend::get_centrifugo_jwt;
+use crate::backend::{generate_centrifugo_subscription_jwt, get_centrifugo_jwt};
 use utils::{config::wss_base_url, updates::Update};
 
 #[derive(Clone)]
@@ -14,10 +19,13 @@ pub struct CentrifugoContext {
 }
 
 type Subscriber = Rc<RefCell<dyn FnMut(Update)>>;
+type WsSink = Rc<Mutex<Option<SplitSink<WebSocket, Message>>>>;
 
 pub struct CentrifugoClient {
     subscribers: Rc<RefCell<HashMap<String, Vec<Subscriber>>>>,
-    ws: Rc<Mutex<Option<WebSocket>>>,
+    ws: WsSink,
+    next_id: Rc<Cell<u32>>,
+    refresh_tasks: Rc<RefCell<HashMap<String, Task>>>,
 }
 
 impl CentrifugoClient {
@@ -25,6 +33,8 @@ impl CentrifugoClient {
         Self {
             subscribers: Rc::new(RefCell::new(HashMap::new())),
             ws: Rc::new(Mutex::new(None)),
+            next_id: Rc::new(Cell::new(1)),
+            refresh_tasks: Rc::new(RefCell::new(HashMap::new())),
         }
     }
 
@@ -38,37 +48,31 @@ impl CentrifugoClient {
             base_url, token
         );
 
-        let mut ws = WebSocket::open(&ws_url).context("Failed to open WebSocket")?;
+        let ws = WebSocket::open(&ws_url).context("Failed to open WebSocket")?;
+        let (mut sink, mut stream) = ws.split();
 
         let connect_msg = serde_json::json!({
-            "id": 1,
+            "id": next_command_id(&self.next_id),
             "connect": { "token": token }
         });
-        ws.send(Message::Text(connect_msg.to_string()))
+        sink.send(Message::Text(connect_msg.to_string()))
             .await
             .context("Failed to send connect message")?;
 
-        *self.ws.lock().await = Some(ws);
+        *self.ws.lock().await = Some(sink);
+
+        let channels: Vec<String> = self.subscribers.borrow().keys().cloned().collect();
+        for channel in channels {
+            if let Err(e) = self.subscribe_channel(&channel).await {
+                error!("Failed to subscribe to {}: {}", channel, e);
+            }
+        }
 
         let ws_clone = self.ws.clone();
         let subs_clone = self.subscribers.clone();
 
         spawn(async move {
-            loop {
-                let msg_opt = {
-                    let mut ws_lock = ws_clone.lock().await;
-                    if let Some(ws) = ws_lock.as_mut() {
-                        ws.next().await
-                    } else {
-                        None
-                    }
-                };
-
-                let msg = match msg_opt {
-                    Some(msg) => msg,
-                    None => break,
-                };
-
+            while let Some(msg) = stream.next().await {
                 if let Ok(Message::Text(txt)) = msg {
                     if txt == "{}" {
                         if let Some(ws) = &mut *ws_clone.lock().await {
@@ -93,13 +97,6 @@ impl CentrifugoClient {
                     let Some(data) = pub_data.get("data") else {
                         continue;
                     };
-                    if txt == "{}" {
-                        let mut ws_lock = ws_clone.lock().await;
-                        if let Some(ws) = ws_lock.as_mut() {
-                            ws.send(Message::Text("{}".to_string())).await.ok();
-                        }
-                        continue;
-                    }
 
                     match serde_json::from_value::<Update>(data.clone()) {
                         Ok(update) => {
@@ -123,22 +120,90 @@ impl CentrifugoClient {
     where
         F: FnMut(Update) + 'static,
     {
-        self.subscribers
-            .borrow_mut()
-            .entry(channel.to_string())
-            .or_default()
-            .push(Rc::new(RefCell::new(callback)));
-
-        if let Some(ws) = &mut *self.ws.lock().await {
-            let subscribe_msg = serde_json::json!({
-                "id": 2,
-                "subscribe": { "channel": channel }
-            });
-            ws.send(Message::Text(subscribe_msg.to_string()))
-                .await
-                .context("Failed to send subscribe message")?;
+        let first_subscriber = {
+            let mut subscribers = self.subscribers.borrow_mut();
+            let listeners = subscribers.entry(channel.to_string()).or_default();
+            listeners.push(Rc::new(RefCell::new(callback)));
+            listeners.len() == 1
+        };
+
+        if first_subscriber && self.ws.lock().await.is_some() {
+            self.subscribe_channel(channel).await?;
         }
 
         Ok(())
     }
+
+    async fn subscribe_channel(&self, channel: &str) -> anyhow::Result<()> {
+        let (token, mut expires_at) =
+            generate_centrifugo_subscription_jwt(channel.to_string()).await?;
+
+        send_command(
+            &self.ws,
+            &self.next_id,
+            "subscribe",
+            serde_json::json!({ "channel": channel, "token": token }),
+        )
+        .await?;
+
+        let ws = self.ws.clone();
+        let next_id = self.next_id.clone();
+        let channel = channel.to_string();
+        let channel_key = channel.clone();
+
+        let task = spawn(async move {
+            loop {
+                let refresh_in = (expires_at - Utc::now().naive_utc()).num_milliseconds() - 60_000;
+                gloo_timers::future::TimeoutFuture::new(refresh_in.max(0) as u32).await;
+
+                let token = match generate_centrifugo_subscription_jwt(channel.clone()).await {
+                    Ok((token, new_expires_at)) => {
+                        expires_at = new_expires_at;
+                        token
+                    }
+                    Err(e) => {
+                        error!("Failed to refresh subscription to {}: {}", channel, e);
+                        break;
+                    }
+                };
+
+                let params = serde_json::json!({ "channel": channel, "token": token });
+                if let Err(e) = send_command(&ws, &next_id, "sub_refresh", params).await {
+                    error!("Failed to refresh subscription to {}: {}", channel, e);
+                    break;
+                }
+            }
+        });
+        if let Some(previous) = self.refresh_tasks.borrow_mut().insert(channel_key, task) {
+            previous.cancel();
+        }
+
+        Ok(())
+    }
+}
+
+fn next_command_id(next_id: &Cell<u32>) -> u32 {
+    let id = next_id.get();
+    next_id.set(id + 1);
+    id
+}
+
+async fn send_command(
+    ws: &WsSink,
+    next_id: &Cell<u32>,
+    method: &str,
+    params: Value,
+) -> anyhow::Result<()> {
+    let mut command = serde_json::json!({ "id": next_command_id(next_id) });
+    command[method] = params;
+
+    let mut ws = ws.lock().await;
+    let Some(ws) = ws.as_mut() else {
+        anyhow::bail!("WebSocket is not connected");
+    };
+    ws.send(Message::Text(command.to_string()))
+        .await
+        .context(format!("Failed to send {} command", method))?;
+
+    Ok(())
 }
@@ -1,12 +1,38 @@
 pub use dioxus::prelude::*;
+use uuid::Uuid;
+
+use crate::{backend::avatar_url, presence::is_online};
+
+const AVATAR_SIZE: u32 = 128;
 
 #[component]
-pub fn Avatar(email_hash: String) -> Element {
+pub fn Avatar(
+    user_uuid: Uuid,
+    avatar_version: Option<i64>,
+    #[props(default = true)] show_presence: bool,
+) -> Element {
+    let online = show_presence && is_online(user_uuid);
+    let src = use_resource(use_reactive!(|(user_uuid, avatar_version)| async move {
+        avatar_url(user_uuid, avatar_version, AVATAR_SIZE).await
+    }));
+
     rsx! {
-        img {
-            class: "rounded-full",
-            src: format!("https://www.gravatar.com/avatar/{}??s=200&d=identicon", email_hash),
-            alt: "User Avatar",
+        div {
+            class: "relative w-full h-full",
+
+            { if let Some(src) = src.read().clone() { rsx! {
+                img {
+                    class: "rounded-full",
+                    src,
+                    alt: "User Avatar",
+                }
+            } } else { rsx! {
+                div { class: "rounded-full w-full h-full bg-gray-200" }
+            } } }
+
+            { if online { rsx! {
+                span { class: "absolute bottom-0 right-0 w-1/4 h-1/4 min-w-2 min-h-2 rounded-full bg-green-500 border-2 border-white" }
+            } } else { rsx! {} } }
         }
     }
 }
@@ -1,7 +1,7 @@
 use dioxus::prelude::*;
 use serde::Deserialize;
 
-use crate::backend::Request;
+use crate::backend::{Request, revoke_session};
 use utils::config::{endpoints::auth::URI_LOGOUT, on_auth_base_url};
 
 #[derive(Deserialize, Debug, Clone)]
@@ -21,6 +21,10 @@ pub fn LogOut() -> Element {
 }
 
 pub async fn logout() {
+    if let Err(e) = revoke_session().await {
+        error!("Failed to revoke session: {}", e);
+    }
+
     match Request::get(&on_auth_base_url(URI_LOGOUT).await)
         .build()
         .send_decode::<LogOutResponse>()
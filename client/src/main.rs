@@ -6,6 +6,7 @@ mod centrifugo;
 mod components;
 mod macros;
 mod panels;
+mod presence;
 mod views;
 
 use dioxus::{logger::tracing::Level, prelude::*};
@@ -1,34 +1,329 @@
+use chrono::Utc;
 use dioxus::prelude::*;
+use std::{
+    cell::{Cell, RefCell},
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+use uuid::Uuid;
 
 use crate::{
     Route,
-    backend::{ApiData, list_chats, use_api_data},
-    components::{Header, HeaderButton, HeaderText, Item, SmallIconButton, Spinner},
+    backend::{ApiData, get_chat, get_user, list_chats, list_folders, my_user, use_api_data},
+    centrifugo::CentrifugoContext,
+    components::{Avatar, Header, HeaderButton, HeaderText, Item, SmallIconButton, Spinner},
+    presence::{load_presence, set_presence},
+};
+
+use super::folders::FolderTabs;
+use utils::{
+    LogError,
+    data::{ChatFolder, ChatInfo, MessagePreview, ModerationAction},
+    updates::Update,
 };
-use utils::data::ChatInfo;
 
 #[derive(Clone)]
 pub struct ChatsContext {
     chats: Signal<ApiData<Vec<ChatInfo>>>,
+    folders: Signal<ApiData<Vec<ChatFolder>>>,
 }
 
+pub static USER_UPDATES: GlobalSignal<Vec<Update>> = GlobalSignal::new(Vec::new);
+pub static CHAT_LIST_UPDATES: GlobalSignal<Vec<(Uuid, Update)>> = GlobalSignal::new(Vec::new);
+pub static UNREAD_CHATS: GlobalSignal<HashSet<Uuid>> = GlobalSignal::new(HashSet::new);
+
 #[component]
 pub fn LeftChats() -> Element {
     {
         let chats = use_api_data(|| async { list_chats().await });
-        let context = ChatsContext { chats };
+        let folders = use_api_data(|| async { list_folders().await });
+        let context = ChatsContext { chats, folders };
         use_context_provider(|| context.clone());
     }
 
     let navigator = navigator();
+    let centrifugo = use_context::<CentrifugoContext>();
+    let mut show_archived = use_signal(|| false);
+    let mut notices = use_signal(Vec::<&'static str>::new);
+    let selected_folder = use_signal(|| None::<Uuid>);
 
     let context = use_context::<ChatsContext>();
+    let chats_signal = context.chats;
+    let folders_signal = context.folders;
+
+    let open_chat = use_hook(|| Rc::new(Cell::new(None::<Uuid>)));
+    open_chat.set(match use_route::<Route>() {
+        Route::ViewChat { uuid } => Uuid::parse_str(&uuid).ok(),
+        _ => None,
+    });
+
+    let subscribed = use_hook(|| Rc::new(RefCell::new(HashSet::<Uuid>::new())));
+    use_effect({
+        let centrifugo = centrifugo.clone();
+        move || {
+            let chats = chats_signal.read();
+            let Some(chats) = chats.as_ref() else {
+                return;
+            };
+
+            let counterparts: Vec<Uuid> = chats
+                .iter()
+                .filter(|chat| !subscribed.borrow().contains(&chat.uuid))
+                .filter_map(|chat| chat.counterpart.as_ref().map(|c| c.uuid))
+                .collect();
+            spawn(load_presence(counterparts));
+
+            for chat in chats {
+                if !subscribed.borrow_mut().insert(chat.uuid) {
+                    continue;
+                }
+
+                let chat_uuid = chat.uuid;
+                let centrifugo = centrifugo.clone();
+                spawn(async move {
+                    centrifugo
+                        .client
+                        .subscribe(&format!("chat_{}", chat_uuid), move |update| {
+                            CHAT_LIST_UPDATES.write().push((chat_uuid, update));
+                        })
+                        .await
+                        .log_error()
+                        .ok();
+                });
+            }
+        }
+    });
+
+    use_future(move || {
+        let centrifugo = centrifugo.clone();
+        let open_chat = open_chat.clone();
+        let mut chats = chats_signal;
+        let mut folders = folders_signal;
+        async move {
+            let Ok(my_user) = my_user().await.log_error() else {
+                return;
+            };
+
+            centrifugo
+                .client
+                .subscribe(&format!("user_{}", my_user.uuid), move |update| {
+                    USER_UPDATES.write().push(update);
+                })
+                .await
+                .log_error()
+                .ok();
+
+            let mut nicknames = HashMap::from([(my_user.uuid, my_user.nickname.clone())]);
+
+            loop {
+                gloo_timers::future::TimeoutFuture::new(100).await;
+
+                if let Some(open_uuid) = open_chat.get()
+                    && UNREAD_CHATS.read().contains(&open_uuid)
+                {
+                    UNREAD_CHATS.write().remove(&open_uuid);
+                }
+
+                let updates = USER_UPDATES.read().clone();
+                if !updates.is_empty() {
+                    USER_UPDATES.write().clear();
+
+                    if let Some(Update::UpdateFolders(new_folders)) = updates
+                        .iter()
+                        .rev()
+                        .find(|update| matches!(update, Update::UpdateFolders(_)))
+                    {
+                        folders.set(ApiData::Loaded(new_folders.clone()));
+                    }
+
+                    if let ApiData::Loaded(chats) = &mut *chats.write() {
+                        for update in updates {
+                            match update {
+                                Update::NewChat(chat) => {
+                                    if !chats.iter().any(|c| c.uuid == chat.uuid) {
+                                        chats.push(chat);
+                                    }
+                                }
+                                Update::UpdateChatPreferences(payload) => {
+                                    if let Some(chat) =
+                                        chats.iter_mut().find(|c| c.uuid == payload.chat_uuid)
+                                    {
+                                        chat.preferences = payload.preferences;
+                                    }
+                                }
+                                Update::ChatRemoved(chat_uuid) => {
+                                    chats.retain(|c| c.uuid != chat_uuid);
+                                    UNREAD_CHATS.write().remove(&chat_uuid);
+                                    if open_chat.get() == Some(chat_uuid) {
+                                        navigator.replace(Route::ViewChats);
+                                    }
+                                }
+                                Update::ReportResolved(payload) => {
+                                    notices.write().push(report_notice(payload.action));
+                                }
+                                Update::PresenceChanged(presence) => {
+                                    set_presence(presence);
+                                }
+
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+
+                let updates = CHAT_LIST_UPDATES.read().clone();
+                if !updates.is_empty() {
+                    CHAT_LIST_UPDATES.write().clear();
+
+                    let now = Utc::now().naive_utc();
+                    for (chat_uuid, update) in updates {
+                        match update {
+                            Update::NewMessage(message) => {
+                                let sender_nickname = match nicknames.get(&message.sender_uuid) {
+                                    Some(nickname) => nickname.clone(),
+                                    None => match get_user(message.sender_uuid).await.log_error() {
+                                        Ok(user) => {
+                                            nicknames.insert(user.uuid, user.nickname.clone());
+                                            user.nickname
+                                        }
+                                        Err(_) => continue,
+                                    },
+                                };
+
+                                let mut chats_guard = chats.write();
+                                let ApiData::Loaded(chats) = &mut *chats_guard else {
+                                    continue;
+                                };
+                                let Some(chat) = chats.iter_mut().find(|c| c.uuid == chat_uuid)
+                                else {
+                                    continue;
+                                };
+
+                                chat.set_last_message(MessagePreview {
+                                    uuid: message.uuid,
+                                    sender_uuid: message.sender_uuid,
+                                    sender_nickname,
+                                    content: MessagePreview::truncate(&message.content),
+                                    created_at: message.created_at,
+                                });
+
+                                if message.sender_uuid != my_user.uuid
+                                    && open_chat.get() != Some(chat_uuid)
+                                    && !chat.preferences.is_muted(now)
+                                {
+                                    UNREAD_CHATS.write().insert(chat_uuid);
+                                }
+                            }
+                            Update::UpdateMessage(payload) => {
+                                if let ApiData::Loaded(chats) = &mut *chats.write()
+                                    && let Some(preview) = chats
+                                        .iter_mut()
+                                        .find(|c| c.uuid == chat_uuid)
+                                        .and_then(|c| c.last_message.as_mut())
+                                    && preview.uuid == payload.uuid
+                                {
+                                    preview.content =
+                                        MessagePreview::truncate(&payload.new_content);
+                                }
+                            }
+                            Update::PresenceChanged(presence) => {
+                                set_presence(presence);
+                            }
+                            Update::SlowModeChanged(payload) => {
+                                if let ApiData::Loaded(chats) = &mut *chats.write()
+                                    && let Some(chat) =
+                                        chats.iter_mut().find(|c| c.uuid == payload.chat_uuid)
+                                {
+                                    chat.slow_mode = payload.slow_mode;
+                                }
+                            }
+                            Update::UserUpdated(user) => {
+                                nicknames.insert(user.uuid, user.nickname.clone());
+
+                                if let ApiData::Loaded(chats) = &mut *chats.write()
+                                    && let Some(chat) =
+                                        chats.iter_mut().find(|c| c.uuid == chat_uuid)
+                                {
+                                    if let Some(counterpart) = &mut chat.counterpart
+                                        && counterpart.uuid == user.uuid
+                                    {
+                                        chat.name = user.nickname.clone();
+                                        *counterpart = user.clone();
+                                    }
+                                    if let Some(preview) = &mut chat.last_message
+                                        && preview.sender_uuid == user.uuid
+                                    {
+                                        preview.sender_nickname = user.nickname.clone();
+                                    }
+                                }
+                            }
+                            Update::DeleteMessage(payload) => {
+                                let previewed = chats
+                                    .read()
+                                    .as_ref()
+                                    .and_then(|chats| chats.iter().find(|c| c.uuid == chat_uuid))
+                                    .and_then(|c| c.last_message.as_ref())
+                                    .is_some_and(|preview| preview.uuid == payload.message_uuid);
+                                if !previewed {
+                                    continue;
+                                }
+
+                                if let Ok(chat) = get_chat(chat_uuid).await.log_error()
+                                    && let ApiData::Loaded(chats) = &mut *chats.write()
+                                    && let Some(existing) =
+                                        chats.iter_mut().find(|c| c.uuid == chat_uuid)
+                                {
+                                    *existing = chat;
+                                }
+                            }
+
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+    });
+
     let chats = context.chats.read();
     if chats.is_loading() || chats.as_ref().is_none() {
         return rsx! { Spinner {} };
     }
     let chats = chats.as_ref().unwrap();
 
+    let now = Utc::now().naive_utc();
+    let folder = selected_folder.read().and_then(|folder_uuid| {
+        context
+            .folders
+            .read()
+            .as_ref()
+            .and_then(|folders| folders.iter().find(|f| f.uuid == folder_uuid).cloned())
+    });
+    let in_folder = |chat: &ChatInfo| {
+        folder.as_ref().is_none_or(|folder| {
+            folder.contains(chat, UNREAD_CHATS.read().contains(&chat.uuid), now)
+        })
+    };
+
+    let mut active: Vec<&ChatInfo> = chats
+        .iter()
+        .filter(|c| !c.preferences.archived && in_folder(c))
+        .collect();
+    active.sort_by_key(|c| {
+        (
+            c.preferences.pinned.is_none(),
+            c.preferences.pinned,
+            Reverse(c.last_activity),
+        )
+    });
+    let mut archived: Vec<&ChatInfo> = chats
+        .iter()
+        .filter(|c| c.preferences.archived && in_folder(c))
+        .collect();
+    archived.sort_by_key(|c| Reverse(c.last_activity));
+    let archived_count = archived.len();
+
     rsx! {
         Header {
             left: rsx! {
@@ -49,20 +344,114 @@ pub fn LeftChats() -> Element {
             },
         }
 
-        { chats.iter().map(|chat| {
-            let uuid = chat.uuid;
-            rsx! {
-                Item {
+        FolderTabs { folders: context.folders, selected: selected_folder }
+
+        { notices.read().iter().enumerate().map(|(index, notice)| rsx! {
+            Item {
+                div {
+                    class: "flex flex-row p-2 text-sm bg-blue-50",
+
+                    p { class: "flex-1", "{notice}" }
+
                     button {
-                        class: "text-left p-2 w-full h-full hover:bg-gray-300 cursor-pointer",
+                        class: "ml-2 text-gray-500 hover:text-gray-700 cursor-pointer",
                         onclick: move |_| {
-                            navigator.replace(Route::ViewChat { uuid: uuid.to_string() });
+                            notices.write().remove(index);
                         },
 
-                        "{chat.name}"
+                        "✕"
                     }
                 }
             }
         }) }
+
+        { active.into_iter().map(|chat| rsx! {
+            ChatItem { chat: chat.clone() }
+        }) }
+
+        { if !archived.is_empty() { rsx! {
+            Item {
+                button {
+                    class: "text-left p-2 w-full h-full text-gray-500 hover:bg-gray-300 cursor-pointer",
+                    onclick: move |_| {
+                        let shown = *show_archived.read();
+                        show_archived.set(!shown);
+                    },
+
+                    "Archived ({archived_count})"
+                }
+            }
+
+            { if *show_archived.read() { rsx! {
+                { archived.iter().copied().map(|chat| rsx! {
+                    ChatItem { chat: chat.clone() }
+                }) }
+            } } else { rsx! {} } }
+        } } else { rsx! {} } }
+    }
+}
+
+fn report_notice(action: ModerationAction) -> &'static str {
+    match action {
+        ModerationAction::Dismiss => "Your report was reviewed. No action was needed.",
+        ModerationAction::DeleteMessage => "Your report was reviewed and the message was removed.",
+        ModerationAction::RemoveFromGroup => {
+            "Your report was reviewed and the sender was removed from the group."
+        }
+        ModerationAction::BanUser => "Your report was reviewed and the sender was banned.",
+    }
+}
+
+#[component]
+pub fn ChatItem(chat: ChatInfo) -> Element {
+    let navigator = navigator();
+    let uuid = chat.uuid;
+
+    let muted = chat.preferences.is_muted(Utc::now().naive_utc());
+    let unread = UNREAD_CHATS.read().contains(&uuid);
+    let weight = if unread { "font-bold" } else { "" };
+
+    rsx! {
+        Item {
+            button {
+                class: "flex flex-row text-left p-2 w-full h-full hover:bg-gray-300 cursor-pointer {weight}",
+                onclick: move |_| {
+                    navigator.replace(Route::ViewChat { uuid: uuid.to_string() });
+                },
+
+                { if let Some(counterpart) = &chat.counterpart { rsx! {
+                    div {
+                        class: "flex-shrink-0 w-6 h-6 mr-2",
+
+                        Avatar { user_uuid: counterpart.uuid, avatar_version: counterpart.avatar_version }
+                    }
+                } } else { rsx! {} } }
+
+                div {
+                    class: "flex flex-col flex-1 min-w-0",
+
+                    p { "{chat.name}" }
+
+                    { if let Some(preview) = &chat.last_message { rsx! {
+                        p {
+                            class: "text-xs text-gray-500 truncate",
+                            "{preview.sender_nickname}: {preview.content}"
+                        }
+                    } } else { rsx! {} } }
+                }
+
+                { if chat.preferences.pinned.is_some() { rsx! {
+                    span { class: "ml-1 text-xs", "📌" }
+                } } else { rsx! {} } }
+
+                { if muted { rsx! {
+                    span { class: "ml-1 text-xs", "🔕" }
+                } } else { rsx! {} } }
+
+                { if unread { rsx! {
+                    span { class: "ml-1 w-2 h-2 self-center rounded-full bg-blue-500" }
+                } } else { rsx! {} } }
+            }
+        }
     }
 }
@@ -0,0 +1,185 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+
+use crate::backend::{ApiData, delete_folder, save_folder};
+use utils::{
+    LogError,
+    data::{ChatFolder, FolderFilters},
+};
+
+#[component]
+pub fn FolderTabs(
+    folders: Signal<ApiData<Vec<ChatFolder>>>,
+    selected: Signal<Option<Uuid>>,
+) -> Element {
+    let mut show_new_folder = use_signal(|| false);
+
+    let folders_guard = folders.read();
+    let folder_list = folders_guard.as_ref().cloned().unwrap_or_default();
+    let current = *selected.read();
+
+    rsx! {
+        div {
+            class: "flex flex-row flex-wrap p-2 border-b border-gray-300",
+
+            FolderTab {
+                name: "All".to_string(),
+                active: current.is_none(),
+                onclick: move |_| selected.set(None),
+            }
+
+            { folder_list.into_iter().map(|folder| {
+                let folder_uuid = folder.uuid;
+                rsx! {
+                    FolderTab {
+                        name: folder.name.clone(),
+                        active: current == Some(folder_uuid),
+                        onclick: move |_| selected.set(Some(folder_uuid)),
+                    }
+                }
+            }) }
+
+            button {
+                class: "text-sm px-3 py-1 rounded-2xl mr-2 mb-1 bg-gray-200 hover:bg-gray-300",
+                onclick: move |_| {
+                    let shown = *show_new_folder.read();
+                    show_new_folder.set(!shown);
+                },
+
+                "+"
+            }
+
+            { if let Some(folder_uuid) = current { rsx! {
+                button {
+                    class: "text-sm px-3 py-1 rounded-2xl mr-2 mb-1 bg-red-200 hover:bg-red-300",
+                    onclick: move |_| {
+                        spawn(async move {
+                            if delete_folder(folder_uuid).await.log_error().is_ok() {
+                                selected.set(None);
+                                if let ApiData::Loaded(folders) = &mut *folders.write() {
+                                    folders.retain(|f| f.uuid != folder_uuid);
+                                }
+                            }
+                        });
+                    },
+
+                    "Delete folder"
+                }
+            } } else { rsx! {} } }
+        }
+
+        { if *show_new_folder.read() { rsx! {
+            NewFolder { folders, show: show_new_folder }
+        } } else { rsx! {} } }
+    }
+}
+
+#[component]
+fn FolderTab(name: String, active: bool, onclick: EventHandler<MouseEvent>) -> Element {
+    let color = if active {
+        "bg-blue-300"
+    } else {
+        "bg-blue-100 hover:bg-blue-200"
+    };
+
+    rsx! {
+        button {
+            class: "text-sm px-3 py-1 rounded-2xl mr-2 mb-1 {color}",
+            onclick: move |e| onclick.call(e),
+
+            "{name}"
+        }
+    }
+}
+
+#[component]
+fn NewFolder(folders: Signal<ApiData<Vec<ChatFolder>>>, show: Signal<bool>) -> Element {
+    let mut name = use_signal(String::new);
+    let mut include_all = use_signal(|| true);
+    let mut filters = use_signal(FolderFilters::default);
+
+    rsx! {
+        form {
+            class: "flex flex-col p-2 border-b border-gray-300 text-sm",
+            onsubmit: move |e| {
+                e.prevent_default();
+                let folder_name = name.read().trim().to_string();
+                if folder_name.is_empty() {
+                    return;
+                }
+
+                let folder_include_all = *include_all.read();
+                let folder_filters = *filters.read();
+                spawn(async move {
+                    if let Ok(folder) =
+                        save_folder(None, folder_name, folder_include_all, Vec::new(), folder_filters)
+                            .await
+                            .log_error()
+                    {
+                        if let ApiData::Loaded(folders) = &mut *folders.write()
+                            && !folders.iter().any(|f| f.uuid == folder.uuid)
+                        {
+                            folders.push(folder);
+                        }
+                        show.set(false);
+                    }
+                });
+            },
+
+            input {
+                class: "p-2 mb-2 border border-gray-300 rounded",
+                placeholder: "Folder name",
+                r#type: "text",
+                value: "{name}",
+                oninput: move |e| name.set(e.value()),
+            }
+
+            label {
+                input {
+                    class: "mr-2",
+                    r#type: "checkbox",
+                    checked: *include_all.read(),
+                    onchange: move |e| include_all.set(e.checked()),
+                }
+                "All chats"
+            }
+
+            label {
+                input {
+                    class: "mr-2",
+                    r#type: "checkbox",
+                    checked: filters.read().groups_only,
+                    onchange: move |e| filters.write().groups_only = e.checked(),
+                }
+                "Groups only"
+            }
+
+            label {
+                input {
+                    class: "mr-2",
+                    r#type: "checkbox",
+                    checked: filters.read().unread_only,
+                    onchange: move |e| filters.write().unread_only = e.checked(),
+                }
+                "Unread only"
+            }
+
+            label {
+                input {
+                    class: "mr-2",
+                    r#type: "checkbox",
+                    checked: filters.read().exclude_muted,
+                    onchange: move |e| filters.write().exclude_muted = e.checked(),
+                }
+                "Exclude muted"
+            }
+
+            button {
+                class: "mt-2 text-center text-white bg-blue-600 hover:bg-blue-700 rounded p-2",
+                r#type: "submit",
+
+                "Create folder"
+            }
+        }
+    }
+}
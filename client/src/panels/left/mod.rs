@@ -1,4 +1,5 @@
 mod chats;
+mod folders;
 mod settings;
 mod users;
 
@@ -2,32 +2,43 @@ use dioxus::prelude::*;
 
 use crate::{
     Route,
-    backend::{ApiData, list_users, use_api_data},
+    backend::{ApiData, CONTACTS, reload_contacts, use_user_search},
     components::{Avatar, Header, HeaderText, Item, Spinner},
 };
-use utils::data::UserInfo;
-
-#[derive(Clone)]
-pub struct UsersContext {
-    users: Signal<ApiData<Vec<UserInfo>>>,
-}
 
 #[component]
 pub fn LeftUsers() -> Element {
-    {
-        let users = use_api_data(|| async { list_users(true).await });
-        let context = UsersContext { users };
-        use_context_provider(|| context.clone());
-    }
-
     let navigator = navigator();
+    let search = use_user_search();
+    let mut query = search.query;
 
-    let context = use_context::<UsersContext>();
-    let users = context.users.read();
-    if users.is_loading() || users.as_ref().is_none() {
-        return rsx! { Spinner {} };
-    }
-    let users = users.as_ref().unwrap();
+    use_future(reload_contacts);
+
+    let searching = !query.read().trim().is_empty();
+    let results = if searching {
+        search.results.read().clone()
+    } else {
+        match &*CONTACTS.read() {
+            ApiData::Loading => ApiData::Loading,
+            ApiData::Loaded(contacts) => ApiData::Loaded(
+                contacts
+                    .iter()
+                    .map(|contact| {
+                        let mut user = contact.user.clone();
+                        user.nickname = contact.display_name().to_string();
+                        user
+                    })
+                    .collect(),
+            ),
+            ApiData::Error(err) => ApiData::Error(err.clone()),
+        }
+    };
+    let users = results.as_ref().cloned().unwrap_or_default();
+    let empty_text = if searching {
+        "No users found"
+    } else {
+        "No contacts yet. Search for people by username or nickname"
+    };
 
     rsx! {
         Header {
@@ -38,6 +49,26 @@ pub fn LeftUsers() -> Element {
             right: rsx! {},
         }
 
+        div {
+            class: "p-2 border-b border-gray-300",
+
+            input {
+                class: "w-full p-2 border border-gray-300 rounded",
+                r#type: "search",
+                placeholder: "Search users",
+                value: "{query}",
+                oninput: move |e| query.set(e.value()),
+            }
+        }
+
+        { if results.is_loading() { rsx! {
+            Spinner {}
+        } } else if let ApiData::Error(err) = &results { rsx! {
+            p { class: "p-2 text-sm text-red-700", "{err}" }
+        } } else if users.is_empty() { rsx! {
+            p { class: "p-2 text-sm text-gray-500", "{empty_text}" }
+        } } else { rsx! {} } }
+
         { users.iter().map(|user| {
             let username = user.username.clone();
             rsx! {
@@ -52,7 +83,7 @@ pub fn LeftUsers() -> Element {
                         div {
                             class: "flex-shrink-0 w-10 h-10 mr-3",
 
-                            Avatar { email_hash: user.email_hash.clone() },
+                            Avatar { user_uuid: user.uuid, avatar_version: user.avatar_version },
                         }
 
                         div {
@@ -64,5 +95,16 @@ pub fn LeftUsers() -> Element {
                 }
             }
         }) }
+
+        { if searching && search.has_more() { rsx! {
+            Item {
+                button {
+                    class: "text-left p-2 w-full h-full text-gray-500 hover:bg-gray-300 cursor-pointer",
+                    onclick: move |_| search.load_more(),
+
+                    "Load more"
+                }
+            }
+        } } else { rsx! {} } }
     }
 }
@@ -1,20 +1,29 @@
+use chrono::{Duration, NaiveDateTime, Utc};
 use dioxus::prelude::*;
 use uuid::Uuid;
 
 use crate::{
     Route,
     backend::{
-        chat_users, delete_message, edit_message, get_chat, list_messages, my_user, send_message,
+        ApiData, chat_users, delete_message, edit_message, get_chat, get_user, list_blocked_users,
+        list_chats, list_folders, list_messages, my_user, report_message, save_folder,
+        send_message, set_chat_preferences, set_slow_mode, use_api_data,
     },
     centrifugo::CentrifugoContext,
-    components::{Avatar, Header, HeaderButtonBack, HeaderText, IconButton, Spinner},
+    components::{
+        Avatar, Header, HeaderButton, HeaderButtonBack, HeaderText, IconButton, SmallIconButton,
+        Spinner,
+    },
     panels::{LayoutContext, PanelLayout},
+    presence::{last_seen_text, load_presence, set_presence},
     verify_uuid,
 };
 use utils::{
     LogError,
-    data::{ChatInfo, MessageInfo, UserInfo},
+    data::{ChatFolder, ChatInfo, ChatKind, ChatPreferences, MessageInfo, UserInfo},
+    errors::ApiError,
     updates::Update,
+    validation::{Reason, validate_message},
 };
 
 #[derive(Clone, PartialEq, Debug)]
@@ -26,7 +35,9 @@ pub enum ChatState {
         my_user: UserInfo,
         chat: ChatInfo,
         members: Vec<UserInfo>,
+        former_members: Vec<UserInfo>,
         messages: Vec<MessageInfo>,
+        blocked: Vec<Uuid>,
     },
 }
 
@@ -39,6 +50,9 @@ pub enum Interaction {
     Delete {
         uuid: Uuid,
     },
+    Report {
+        uuid: Uuid,
+    },
     Reply {
         uuid: Uuid,
         content: String,
@@ -57,6 +71,7 @@ pub fn RightChat(uuid: String) -> Element {
     let uuid = verify_uuid!(uuid);
     let centrifugo = use_context::<CentrifugoContext>();
     let mut state = use_signal(|| ChatState::Uninitialized);
+    let mut show_options = use_signal(|| false);
     {
         let default_interaction = use_signal(|| Interaction::None);
         use_context_provider(|| default_interaction);
@@ -73,20 +88,51 @@ pub fn RightChat(uuid: String) -> Element {
             *state.write() = ChatState::Loading;
 
             spawn(async move {
-                let (chat_res, members_res, my_user_res, messages_res) = futures::join!(
+                let (chat_res, members_res, my_user_res, messages_res, blocked_res) = futures::join!(
                     get_chat(uuid),
                     chat_users(uuid),
                     my_user(),
                     list_messages(uuid),
+                    list_blocked_users(),
                 );
 
+                let members = members_res.log_error().expect("Failed to fetch chat users");
+                let member_uuids = members.iter().map(|m| m.uuid).collect();
+                let messages = messages_res.log_error().expect("Failed to fetch messages");
+
+                let mut former_uuids: Vec<Uuid> = messages
+                    .iter()
+                    .flat_map(|m| {
+                        std::iter::once(m.sender_uuid)
+                            .chain(m.reply.as_ref().map(|r| r.sender_uuid))
+                    })
+                    .filter(|sender_uuid| !members.iter().any(|u| u.uuid == *sender_uuid))
+                    .collect();
+                former_uuids.sort();
+                former_uuids.dedup();
+                let former_members =
+                    futures::future::join_all(former_uuids.into_iter().map(get_user))
+                        .await
+                        .into_iter()
+                        .filter_map(|user| user.log_error().ok())
+                        .collect();
+
                 *state.write() = ChatState::Loaded {
                     uuid,
                     chat: chat_res.log_error().expect("Failed to fetch chat"),
-                    members: members_res.log_error().expect("Failed to fetch chat users"),
+                    members,
+                    former_members,
                     my_user: my_user_res.log_error().expect("Failed to fetch my user"),
-                    messages: messages_res.log_error().expect("Failed to fetch messages"),
+                    messages,
+                    blocked: blocked_res
+                        .log_error()
+                        .expect("Failed to fetch blocked users")
+                        .into_iter()
+                        .map(|u| u.uuid)
+                        .collect(),
                 };
+
+                load_presence(member_uuids).await;
             });
         }
 
@@ -114,7 +160,13 @@ pub fn RightChat(uuid: String) -> Element {
 
                 match &mut *state.write() {
                     ChatState::Uninitialized | ChatState::Loading => continue,
-                    ChatState::Loaded { messages, .. } => {
+                    ChatState::Loaded {
+                        my_user,
+                        chat,
+                        members,
+                        messages,
+                        ..
+                    } => {
                         for (_, update) in updates.iter() {
                             match update {
                                 Update::NewMessage(message) => {
@@ -133,6 +185,28 @@ pub fn RightChat(uuid: String) -> Element {
                                         message.edited_at = Some(payload.edited_at);
                                     }
                                 }
+                                Update::PresenceChanged(presence) => {
+                                    set_presence(*presence);
+                                }
+                                Update::SlowModeChanged(payload) => {
+                                    chat.slow_mode = payload.slow_mode;
+                                }
+                                Update::UserUpdated(user) => {
+                                    if let Some(member) =
+                                        members.iter_mut().find(|m| m.uuid == user.uuid)
+                                    {
+                                        *member = user.clone();
+                                    }
+                                    if my_user.uuid == user.uuid {
+                                        *my_user = user.clone();
+                                    }
+                                    if let Some(counterpart) = &mut chat.counterpart
+                                        && counterpart.uuid == user.uuid
+                                    {
+                                        *counterpart = user.clone();
+                                        chat.name = user.nickname.clone();
+                                    }
+                                }
 
                                 _ => {}
                             }
@@ -152,9 +226,17 @@ pub fn RightChat(uuid: String) -> Element {
             my_user,
             chat,
             members,
+            former_members,
             messages,
+            blocked,
             ..
         } => {
+            let known_users: Vec<UserInfo> = members
+                .iter()
+                .chain(former_members.iter())
+                .cloned()
+                .collect();
+
             rsx! {
                 div {
                     class: "flex flex-col h-full",
@@ -163,25 +245,288 @@ pub fn RightChat(uuid: String) -> Element {
                         left: rsx! { HeaderButtonBack {
                             route: Route::ViewChats,
                         } },
-                        center: rsx! { HeaderText {
-                            text: "{chat.name}"
+                        center: rsx! { div {
+                            class: "flex flex-col items-center",
+
+                            { match chat.counterpart.clone() {
+                                Some(counterpart) => rsx! {
+                                    button {
+                                        class: "cursor-pointer",
+                                        onclick: move |_| {
+                                            navigator().replace(Route::ViewUser {
+                                                username: counterpart.username.clone(),
+                                            });
+                                        },
+
+                                        HeaderText { text: "{chat.name}" }
+                                    }
+                                },
+                                None => rsx! {
+                                    HeaderText { text: "{chat.name}" }
+                                },
+                            } }
+
+                            { match chat.kind {
+                                ChatKind::Private => {
+                                    let status = chat.counterpart.as_ref().and_then(|c| c.active_status(Utc::now().naive_utc())).map(|s| s.label());
+                                    let last_seen = chat.counterpart.as_ref().and_then(|c| last_seen_text(c.uuid));
+                                    rsx! {
+                                        { if let Some(status) = status { rsx! {
+                                            p { class: "text-xs", "{status}" }
+                                        } } else { rsx! {} } }
+                                        { if let Some(last_seen) = last_seen { rsx! {
+                                            p { class: "text-xs text-gray-500", "{last_seen}" }
+                                        } } else { rsx! {} } }
+                                    }
+                                }
+                                ChatKind::Group => rsx! {
+                                    p { class: "text-xs text-gray-500", "{chat.member_count} members" }
+                                },
+                                ChatKind::Broadcast => rsx! {
+                                    p { class: "text-xs text-gray-500", "{chat.member_count} subscribers" }
+                                },
+                            } }
                         } },
-                        right: rsx! {}
+                        right: rsx! { HeaderButton {
+                            SmallIconButton {
+                                alt: "Options".to_string(),
+                                icon: asset!("/assets/icons/options.svg"),
+                                ty: "button".to_string(),
+                                onclick: move |_| {
+                                    let shown = *show_options.read();
+                                    show_options.set(!shown);
+                                },
+                            }
+                        } }
                     }
 
+                    { if *show_options.read() { rsx! {
+                        ChatOptions { chat: chat.clone(), state }
+                    } } else { rsx! {} } }
+
                     div {
                         class: "flex-1 overflow-y-auto p-4 space-y-2 bg-gray-50",
                         id: "message-container",
 
-                        { messages.iter().map(|message| {
-                            message_item(members, my_user, message.clone())
+                        { messages.iter().filter(|message| !blocked.contains(&message.sender_uuid)).map(|message| {
+                            message_item(&known_users, my_user, message.clone())
                         }) }
                     }
 
-                    MessageBox { uuid: *uuid }
+                    { if chat.can_post() { rsx! {
+                        MessageBox {
+                            uuid: *uuid,
+                            slow_mode: chat.slow_mode.filter(|_| !chat.role.is_admin()),
+                        }
+                    } } else { rsx! {
+                        div {
+                            class: "sticky bottom-0 bg-white border-t border-gray-300 p-2 text-center text-sm text-gray-500",
+                            "Only admins can post in this channel"
+                        }
+                    } } }
+                }
+            }
+        }
+    }
+}
+
+fn update_preferences(mut state: Signal<ChatState>, chat_uuid: Uuid, preferences: ChatPreferences) {
+    spawn(async move {
+        if let Err(e) = set_chat_preferences(chat_uuid, preferences.clone()).await {
+            error!("Failed to update chat preferences: {}", e);
+            return;
+        }
+
+        if let ChatState::Loaded { chat, .. } = &mut *state.write() {
+            chat.preferences = preferences;
+        }
+    });
+}
+
+#[component]
+pub fn ChatOptions(chat: ChatInfo, state: Signal<ChatState>) -> Element {
+    let uuid = chat.uuid;
+    let preferences = chat.preferences.clone();
+    let muted = preferences.is_muted(Utc::now().naive_utc());
+    let pin_label = if preferences.pinned.is_some() {
+        "Unpin"
+    } else {
+        "Pin"
+    };
+    let archive_label = if preferences.archived {
+        "Unarchive"
+    } else {
+        "Archive"
+    };
+
+    let slow_mode = chat.slow_mode;
+    let can_set_slow_mode = chat.kind != ChatKind::Private && chat.role.is_admin();
+
+    let mut folders = use_api_data(|| async { list_folders().await });
+    let folder_list: Vec<ChatFolder> = folders
+        .read()
+        .as_ref()
+        .map(|folders| {
+            folders
+                .iter()
+                .filter(|folder| !folder.include_all)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let pin_preferences = preferences.clone();
+    let mute_preferences = preferences.clone();
+    let archive_preferences = preferences.clone();
+
+    rsx! {
+        div {
+            class: "flex flex-row flex-wrap p-2 border-b border-gray-300",
+
+            button {
+                class: "bg-blue-200 hover:bg-blue-300 text-sm px-4 py-2 rounded-2xl mr-2",
+                onclick: move |_| {
+                    let preferences = pin_preferences.clone();
+                    spawn(async move {
+                        let pinned = if preferences.pinned.is_some() {
+                            None
+                        } else {
+                            let last_pinned = list_chats()
+                                .await
+                                .log_error()
+                                .ok()
+                                .and_then(|chats| chats.iter().filter_map(|c| c.preferences.pinned).max());
+                            Some(last_pinned.map_or(0, |order| order + 1))
+                        };
+                        update_preferences(state, uuid, ChatPreferences { pinned, ..preferences });
+                    });
+                },
+
+                "{pin_label}"
+            }
+
+            { if muted { rsx! {
+                button {
+                    class: "bg-yellow-200 hover:bg-yellow-300 text-sm px-4 py-2 rounded-2xl mr-2",
+                    onclick: move |_| {
+                        let muted_until = None;
+                        update_preferences(state, uuid, ChatPreferences { muted_until, ..mute_preferences.clone() });
+                    },
+
+                    "Unmute"
+                }
+            } } else { rsx! {
+                { [("Mute for 1 hour", Duration::hours(1)), ("Mute for 8 hours", Duration::hours(8)), ("Mute for a week", Duration::weeks(1))].into_iter().map(|(label, duration)| {
+                    let mute_preferences = mute_preferences.clone();
+                    rsx! {
+                        button {
+                            class: "bg-yellow-200 hover:bg-yellow-300 text-sm px-4 py-2 rounded-2xl mr-2",
+                            onclick: move |_| {
+                                let muted_until = Some(Utc::now().naive_utc() + duration);
+                                update_preferences(state, uuid, ChatPreferences { muted_until, ..mute_preferences.clone() });
+                            },
+
+                            "{label}"
+                        }
+                    }
+                }) }
+            } } }
+
+            button {
+                class: "bg-gray-200 hover:bg-gray-300 text-sm px-4 py-2 rounded-2xl mr-2",
+                onclick: move |_| {
+                    let archived = !archive_preferences.archived;
+                    update_preferences(state, uuid, ChatPreferences { archived, ..archive_preferences.clone() });
+                },
+
+                "{archive_label}"
+            }
+
+            { if can_set_slow_mode { rsx! {
+                { [("Slow mode off", None), ("Slow mode 10s", Some(10)), ("Slow mode 1m", Some(60)), ("Slow mode 5m", Some(300))].into_iter().map(|(label, seconds)| {
+                    let color = if slow_mode == seconds { "bg-purple-300" } else { "bg-purple-200" };
+                    rsx! {
+                        button {
+                            class: "{color} hover:bg-purple-300 text-sm px-4 py-2 rounded-2xl mr-2",
+                            onclick: move |_| {
+                                spawn(async move {
+                                    if set_slow_mode(uuid, seconds).await.log_error().is_ok()
+                                        && let ChatState::Loaded { chat, .. } = &mut *state.write()
+                                    {
+                                        chat.slow_mode = seconds;
+                                    }
+                                });
+                            },
+
+                            "{label}"
+                        }
+                    }
+                }) }
+            } } else { rsx! {} } }
+
+            { folder_list.into_iter().map(|folder| {
+                let included = folder.include.contains(&uuid);
+                let label = if included {
+                    format!("Remove from {}", folder.name)
+                } else {
+                    format!("Add to {}", folder.name)
+                };
+                rsx! {
+                    button {
+                        class: "bg-green-200 hover:bg-green-300 text-sm px-4 py-2 rounded-2xl mr-2",
+                        onclick: move |_| {
+                            let folder = folder.clone();
+                            spawn(async move {
+                                let mut include = folder.include;
+                                if included {
+                                    include.retain(|chat_uuid| *chat_uuid != uuid);
+                                } else {
+                                    include.push(uuid);
+                                }
+
+                                if let Ok(saved) = save_folder(Some(folder.uuid), folder.name, false, include, folder.filters)
+                                    .await
+                                    .log_error()
+                                    && let ApiData::Loaded(folders) = &mut *folders.write()
+                                    && let Some(existing) = folders.iter_mut().find(|f| f.uuid == saved.uuid)
+                                {
+                                    *existing = saved;
+                                }
+                            });
+                        },
+
+                        "{label}"
+                    }
                 }
+            }) }
+        }
+    }
+}
+
+async fn send_with_cooldown(
+    chat_uuid: Uuid,
+    content: String,
+    reply: Option<Uuid>,
+    slow_mode: Option<u32>,
+    mut cooldown_until: Signal<Option<NaiveDateTime>>,
+) {
+    match send_message(chat_uuid, content, reply).await {
+        Ok(()) => {
+            if let Some(seconds) = slow_mode {
+                cooldown_until.set(Some(
+                    Utc::now().naive_utc() + Duration::seconds(seconds.into()),
+                ));
             }
         }
+        Err(e) => {
+            if let Some(retry_after) = e.downcast_ref::<ApiError>().and_then(ApiError::retry_after)
+            {
+                cooldown_until.set(Some(
+                    Utc::now().naive_utc() + Duration::seconds(retry_after as i64),
+                ));
+            }
+            error!("Failed to send message: {}", e);
+        }
     }
 }
 
@@ -289,10 +634,44 @@ pub fn message_item(users: &[UserInfo], my_user: &UserInfo, message: MessageInfo
 
                                     "Delete"
                                 }
-                            } } else { rsx! {} }
+                            } } else { rsx! {
+                                button {
+                                    class: "bg-red-200 hover:bg-red-300 text-sm px-4 py-2 rounded-2xl mr-2",
+                                    onclick: move |e| {
+                                        e.prevent_default();
+                                        interaction.set(Interaction::Report {
+                                            uuid: message_clone_delete.uuid,
+                                        });
+                                    },
+
+                                    "Report"
+                                }
+                            } }
                         }
                     } }
 
+                    Interaction::Report { uuid: context_uuid } if context_uuid == message.uuid => { rsx! {
+                        { ["Spam", "Harassment", "Inappropriate content"].into_iter().map(|reason| rsx! {
+                            button {
+                                class: "bg-red-200 hover:bg-red-300 text-sm px-4 py-2 rounded-2xl mr-2",
+                                onclick: move |e| {
+                                    e.prevent_default();
+                                    interaction.set(Interaction::None);
+                                    spawn({
+                                        let message_uuid = message.uuid;
+                                        async move {
+                                            if let Err(e) = report_message(message_uuid, reason.to_string()).await {
+                                                error!("Failed to report message {}: {}", message_uuid, e);
+                                            }
+                                        }
+                                    });
+                                },
+
+                                "{reason}"
+                            }
+                        }) }
+                    } }
+
                     Interaction::Delete { uuid: context_uuid } if context_uuid == message.uuid => { rsx! {
                         button {
                             class: "bg-red-200 hover:bg-red-300 text-sm px-4 py-2 rounded-2xl mr-2",
@@ -343,6 +722,7 @@ pub fn MessageBubble(
     edited: bool,
 ) -> Element {
     let mut interaction = use_context::<Signal<Interaction>>();
+    let deleted_sender = sender.as_ref().is_some_and(|s| s.deleted);
 
     let bubble_color = match *interaction.read() {
         Interaction::Reply {
@@ -357,7 +737,11 @@ pub fn MessageBubble(
         Interaction::Edit {
             uuid: context_uuid, ..
         } if context_uuid == uuid => "bg-yellow-200",
-        Interaction::Delete { uuid: context_uuid } if context_uuid == uuid => "bg-red-200",
+        Interaction::Delete { uuid: context_uuid } | Interaction::Report { uuid: context_uuid }
+            if context_uuid == uuid =>
+        {
+            "bg-red-200"
+        }
         _ => {
             if is_reply {
                 if is_me { "bg-green-400" } else { "bg-gray-400" }
@@ -372,7 +756,8 @@ pub fn MessageBubble(
     rsx! {
         { if !location_right && let Some(ref sender) = sender { rsx! {
             MessageAvatar {
-                email_hash: sender.email_hash.clone(),
+                user_uuid: sender.uuid,
+                avatar_version: sender.avatar_version,
                 tint: is_reply,
             }
         } } else { rsx! {} } }
@@ -398,6 +783,10 @@ pub fn MessageBubble(
                         }
                     },
 
+                    { if deleted_sender { rsx! {
+                        span { class: "text-xs text-gray-500 mr-2 self-start", "Deleted account" }
+                    } } else { rsx! {} } }
+
                     { if edited { rsx! {
                         img {
                             class: "w-3 h-3 mb-1 mr-1 self-end",
@@ -416,7 +805,8 @@ pub fn MessageBubble(
 
         { if location_right && let Some(ref sender) = sender { rsx! {
             MessageAvatar {
-                email_hash: sender.email_hash.clone(),
+                user_uuid: sender.uuid,
+                avatar_version: sender.avatar_version,
                 tint: is_reply,
             }
         } } else { rsx! {} } }
@@ -424,21 +814,36 @@ pub fn MessageBubble(
 }
 
 #[component]
-pub fn MessageAvatar(email_hash: String, tint: bool) -> Element {
+pub fn MessageAvatar(user_uuid: Uuid, avatar_version: Option<i64>, tint: bool) -> Element {
     let tint = if tint { "brightness-75" } else { "" };
 
     rsx! {
         div {
             class: "flex items-end mr-2 w-9 h-9 ml-2 {tint}",
-            Avatar { email_hash }
+            Avatar { user_uuid, avatar_version, show_presence: false }
         }
     }
 }
 
 #[component]
-pub fn MessageBox(uuid: Uuid) -> Element {
+pub fn MessageBox(uuid: Uuid, slow_mode: Option<u32>) -> Element {
     let mut interaction = use_context::<Signal<Interaction>>();
     let mut message = use_signal(String::new);
+    let mut message_error = use_signal(|| None as Option<String>);
+    let cooldown_until = use_signal(|| None::<NaiveDateTime>);
+    let mut now = use_signal(|| Utc::now().naive_utc());
+
+    use_future(move || async move {
+        loop {
+            gloo_timers::future::TimeoutFuture::new(1000).await;
+            now.set(Utc::now().naive_utc());
+        }
+    });
+
+    let cooldown = cooldown_until
+        .read()
+        .map(|until| ((until - *now.read()).num_milliseconds() + 999) / 1000)
+        .filter(|seconds| *seconds > 0);
 
     let icon = match *interaction.read() {
         Interaction::Edit { .. } => asset!("/assets/icons/edit.svg"),
@@ -505,27 +910,49 @@ pub fn MessageBox(uuid: Uuid) -> Element {
                 _ => { rsx! {} }
             } }
 
+            { if let Some(seconds) = cooldown { rsx! {
+                p {
+                    class: "text-xs text-gray-500 mb-2",
+                    "Slow mode: you can send again in {seconds}s"
+                }
+            } } else { rsx! {} } }
+
+            { if let Some(err) = message_error() { rsx! {
+                p {
+                    class: "text-xs text-red-700 mb-2",
+                    "{err}"
+                }
+            } } else { rsx! {} } }
+
             form {
                 class: "flex gap-2",
                 onsubmit: move |e| {
                     e.prevent_default();
-                    let message_clone = message.read().clone();
-                    let message_clone = message_clone.trim().to_string();
-                    if message_clone.is_empty() {
+                    let content = match validate_message(&message.read()) {
+                        Ok(content) => content,
+                        Err(e) => {
+                            if e.reason != Reason::Empty {
+                                message_error.set(Some(e.to_string()));
+                            }
+                            return;
+                        }
+                    };
+                    message_error.set(None);
+
+                    let cooling_down = cooldown_until
+                        .read()
+                        .is_some_and(|until| until > Utc::now().naive_utc());
+                    if cooling_down && !matches!(*interaction.read(), Interaction::Edit { .. }) {
                         return;
                     }
 
                     match &mut *interaction.write() {
                         Interaction::Edit { uuid: edit_uuid, .. } => {
-                            let new_content = message_clone.trim().to_string();
-                            if new_content.is_empty() {
-                                return;
-                            }
                             message.set(String::new());
                             spawn({
                                 let edit_uuid = *edit_uuid;
                                 async move {
-                                    if let Err(e) = edit_message(edit_uuid, new_content).await {
+                                    if let Err(e) = edit_message(edit_uuid, content).await {
                                         error!("Failed to edit message: {}", e);
                                     }
                                 }
@@ -533,30 +960,14 @@ pub fn MessageBox(uuid: Uuid) -> Element {
                         },
 
                         Interaction::Reply { uuid: reply_uuid, .. } => {
-                            let msg = message.read().trim().to_string();
-                            if msg.is_empty() {
-                                return;
-                            }
                             message.set(String::new());
                             let reply = *reply_uuid;
-                            spawn(async move {
-                                if let Err(e) = send_message(uuid, msg, Some(reply)).await {
-                                    error!("Failed to send message: {}", e);
-                                }
-                            });
+                            spawn(send_with_cooldown(uuid, content, Some(reply), slow_mode, cooldown_until));
                         }
 
                         _ => {
-                            let msg = message.read().trim().to_string();
-                            if msg.is_empty() {
-                                return;
-                            }
                             message.set(String::new());
-                            spawn(async move {
-                                if let Err(e) = send_message(uuid, msg, None).await {
-                                    error!("Failed to send message: {}", e);
-                                }
-                            });
+                            spawn(send_with_cooldown(uuid, content, None, slow_mode, cooldown_until));
                         }
                     }
 
@@ -1,10 +1,13 @@
 use dioxus::prelude::*;
-use utils::data::UserInfo;
+use utils::{
+    data::{ChatKind, UserInfo},
+    validation::validate_group_title,
+};
 use uuid::Uuid;
 
 use crate::{
     Route,
-    backend::{list_users, my_user, new_group, use_api_data},
+    backend::{my_user, new_group, use_user_search},
     components::{
         Avatar, CenteredForm, Header, HeaderButtonBack, HeaderText, IconButton,
         NotFullHeightSpinner,
@@ -26,6 +29,7 @@ pub fn RightNewGroup() -> Element {
     let mut state = use_signal(|| Stage::Title);
     let title: Signal<(bool, Option<String>)> = use_signal(|| (false, None));
     let users: Signal<(bool, Vec<UserInfo>)> = use_signal(|| (false, Vec::new()));
+    let kind = use_signal(|| ChatKind::Group);
     let finalized = use_signal(|| false);
 
     use_effect({
@@ -52,7 +56,8 @@ pub fn RightNewGroup() -> Element {
                         };
                         user_uuids.push(my_uuid);
 
-                        match new_group(title, user_uuids).await {
+                        let kind = *kind.read();
+                        match new_group(title, kind, user_uuids).await {
                             Ok(uuid) => {
                                 navigator.replace(Route::ViewChat {
                                     uuid: uuid.to_string(),
@@ -88,9 +93,9 @@ pub fn RightNewGroup() -> Element {
 
         CenteredForm {
             { match *state.read() {
-                Stage::Title => rsx! { EnterTitle { title } },
+                Stage::Title => rsx! { EnterTitle { title, kind } },
                 Stage::Users => rsx! { AddUsers { users } },
-                Stage::Finalize => rsx! { Finalize { finalized, title, users } },
+                Stage::Finalize => rsx! { Finalize { finalized, title, kind, users } },
                 Stage::End => rsx! {
                     div {
                         class: "my-10",
@@ -106,11 +111,17 @@ pub fn RightNewGroup() -> Element {
 pub fn Finalize(
     finalized: Signal<bool>,
     title: Signal<(bool, Option<String>)>,
+    kind: Signal<ChatKind>,
     users: Signal<(bool, Vec<UserInfo>)>,
 ) -> Element {
     let users_guard = users.read();
     let users_clone = users_guard.1.clone();
 
+    let (kind_label, members_label) = match *kind.read() {
+        ChatKind::Broadcast => ("Broadcast channel", "Subscribers:"),
+        _ => ("Group", "Members:"),
+    };
+
     rsx! {
         p {
             class: "text-xl pb-2 text-center",
@@ -130,7 +141,12 @@ pub fn Finalize(
 
         p {
             class: "text-m text-center mb-4",
-            "Members:"
+            "Type: {kind_label}"
+        }
+
+        p {
+            class: "text-m text-center mb-4",
+            "{members_label}"
         }
 
         { users_clone.iter().map(|user| {
@@ -163,8 +179,10 @@ pub fn Finalize(
 }
 
 #[component]
-pub fn EnterTitle(title: Signal<(bool, Option<String>)>) -> Element {
+pub fn EnterTitle(title: Signal<(bool, Option<String>)>, kind: Signal<ChatKind>) -> Element {
     let mut local_title: Signal<String> = use_signal(|| title.read().1.clone().unwrap_or_default());
+    let mut title_error = use_signal(|| None as Option<String>);
+    let broadcast = *kind.read() == ChatKind::Broadcast;
 
     rsx! {
         p {
@@ -177,11 +195,10 @@ pub fn EnterTitle(title: Signal<(bool, Option<String>)>) -> Element {
             onsubmit: {
                 move |e| {
                     e.prevent_default();
-                    match e.data().get_first("group_name").unwrap() {
-                        FormValue::Text(s) if !s.trim().is_empty() => {
-                            title.write().1 = Some(s.trim().to_string());
-                        },
-                        _ => {}
+                    if let FormValue::Text(s) = e.data().get_first("group_name").unwrap()
+                        && let Ok(valid_title) = validate_group_title(&s)
+                    {
+                        title.write().1 = Some(valid_title);
                     }
                 }
             },
@@ -197,14 +214,35 @@ pub fn EnterTitle(title: Signal<(bool, Option<String>)>) -> Element {
                 },
             },
 
+            { if let Some(err) = title_error() { rsx! {
+                p { class: "text-xs text-red-700 mt-1", "{err}" }
+            } } else { rsx! {} } }
+
+            label {
+                class: "flex flex-row items-center mt-2 text-sm text-gray-600",
+
+                input {
+                    class: "mr-2",
+                    r#type: "checkbox",
+                    checked: broadcast,
+                    onchange: move |e| {
+                        kind.set(if e.checked() { ChatKind::Broadcast } else { ChatKind::Group });
+                    },
+                }
+
+                "Broadcast channel (only admins can post)"
+            }
+
             button {
                 class: "w-full text-center text-white bg-blue-600 hover:bg-blue-700 rounded p-2 mt-4",
                 onclick: move |_| {
-                    let local_title = local_title.read();
-                    if local_title.trim().is_empty() {
-                        return;
+                    match validate_group_title(&local_title.read()) {
+                        Ok(valid_title) => {
+                            title_error.set(None);
+                            title.set((true, Some(valid_title)));
+                        }
+                        Err(e) => title_error.set(Some(e.to_string())),
                     }
-                    title.set((true, Some(local_title.trim().to_string())));
                 },
                 "Next"
             }
@@ -216,17 +254,11 @@ pub fn EnterTitle(title: Signal<(bool, Option<String>)>) -> Element {
 pub fn AddUsers(users: Signal<(bool, Vec<UserInfo>)>) -> Element {
     let mut users = users;
 
-    let all_users = use_api_data(|| async { list_users(true).await });
-
-    {
-        let all_users = all_users.read();
-        if all_users.is_loading() {
-            return rsx! { NotFullHeightSpinner {} };
-        }
-    };
+    let search = use_user_search();
+    let mut query = search.query;
 
-    let all_users_guard = all_users.read();
-    let all_users = all_users_guard.as_ref().unwrap();
+    let results = search.results.read();
+    let all_users = results.as_ref().cloned().unwrap_or_default();
 
     let selected_users = users.read().1.clone();
 
@@ -264,6 +296,18 @@ pub fn AddUsers(users: Signal<(bool, Vec<UserInfo>)>) -> Element {
                 "Add more?"
             }
 
+            input {
+                class: "w-full p-2 mt-2 border border-gray-300 rounded text-base",
+                r#type: "search",
+                placeholder: "Search users",
+                value: "{query}",
+                oninput: move |e| query.set(e.value()),
+            }
+
+            { if results.is_loading() { rsx! {
+                NotFullHeightSpinner {}
+            } } else { rsx! {} } }
+
             { all_users.iter().map(|user| {
                 if selected_users.contains(user) {
                     return rsx! {};
@@ -293,6 +337,15 @@ pub fn AddUsers(users: Signal<(bool, Vec<UserInfo>)>) -> Element {
                 }
             }) }
 
+            { if search.has_more() { rsx! {
+                button {
+                    class: "w-full text-center text-gray-500 hover:bg-gray-300 rounded p-2 mt-2 text-base",
+                    onclick: move |_| search.load_more(),
+
+                    "Load more"
+                }
+            } } else { rsx! {} } }
+
             button {
                 class: "w-full text-center text-white bg-blue-600 hover:bg-blue-700 rounded p-2 mt-4",
                 onclick: move |_| {
@@ -312,7 +365,7 @@ pub fn UserItem(user: UserInfo) -> Element {
             div {
                 class: "flex-shrink-0 w-8 h-8 mr-3",
 
-                Avatar { email_hash: user.email_hash.clone() },
+                Avatar { user_uuid: user.uuid, avatar_version: user.avatar_version },
             }
 
             div {
@@ -1,9 +1,20 @@
+use chrono::{Duration, NaiveDateTime, Utc};
 use dioxus::prelude::*;
+use utils::{
+    LogError,
+    data::{PrivacyAudience, PrivacySettings, TokenScope, UserInfo, UserStatus},
+    validation::{Field, validate_nickname, validate_token_name, validate_username},
+};
+use uuid::Uuid;
 
 use crate::{
     Route,
-    backend::{my_user, use_api_data},
-    components::{Header, HeaderButtonBack, HeaderText, LogOut, Spinner},
+    backend::{
+        ApiData, create_access_token, delete_account, export_data, get_privacy, list_access_tokens,
+        my_user, remove_avatar, revoke_access_token, save_file, save_privacy, set_status,
+        update_profile, upload_avatar, use_api_data, validation_error,
+    },
+    components::{Avatar, Header, HeaderButtonBack, HeaderText, LogOut, Spinner, logout},
     verify_user,
 };
 
@@ -11,13 +22,12 @@ use crate::{
 pub fn SettingsAccount() -> Element {
     let _ = verify_user!();
 
-    let user = use_api_data(|| async { my_user().await });
+    let mut user = use_api_data(|| async { my_user().await });
     if user.read().is_loading() || user.read().as_ref().is_none() {
         return rsx! { Spinner {} };
     }
 
-    let user_guard = user.read();
-    let user = user_guard.as_ref().unwrap().clone();
+    let current_user = user.read().as_ref().unwrap().clone();
 
     rsx! {
         Header {
@@ -35,10 +45,693 @@ pub fn SettingsAccount() -> Element {
 
             p {
                 class: "text-s",
-                "{user.nickname} @{user.username}"
+                "{current_user.nickname} @{current_user.username}"
+            }
+
+            AvatarForm {
+                user: current_user.clone(),
+                onsaved: move |updated: UserInfo| user.set(ApiData::Loaded(updated)),
             }
 
+            ProfileForm {
+                user: current_user.clone(),
+                onsaved: move |updated: UserInfo| user.set(ApiData::Loaded(updated)),
+            }
+
+            StatusForm {
+                user: current_user.clone(),
+                onsaved: move |updated: UserInfo| user.set(ApiData::Loaded(updated)),
+            }
+
+            PrivacyForm {}
+
+            AccessTokensForm {}
+
             LogOut {}
+
+            AccountData { username: current_user.username.clone() }
+        }
+    }
+}
+
+#[component]
+fn AccountData(username: String) -> Element {
+    let mut confirm = use_signal(String::new);
+    let mut error = use_signal(|| None as Option<String>);
+    let confirmed = *confirm.read() == username;
+
+    rsx! {
+        div {
+            class: "flex flex-col border-t border-gray-300 pt-4 space-y-2",
+
+            p { class: "text-sm font-bold", "Your data" }
+
+            button {
+                class: "bg-gray-200 hover:bg-gray-300 text-sm px-4 py-2 rounded-2xl",
+                onclick: move |_| {
+                    spawn(async move {
+                        if let Ok(archive) = export_data().await.log_error() {
+                            save_file("commeator-export.zip", "application/zip", &archive)
+                                .log_error()
+                                .ok();
+                        }
+                    });
+                },
+
+                "Download my data"
+            }
+
+            p {
+                class: "text-xs text-gray-500",
+                "Deleting your account removes you from all chats and cannot be undone. Your messages will show as \"Deleted account\". Type your username to confirm."
+            }
+
+            input {
+                class: "p-2 border border-gray-300 rounded",
+                r#type: "text",
+                placeholder: "{username}",
+                value: "{confirm}",
+                oninput: move |e| confirm.set(e.value()),
+            }
+
+            {
+                match error() {
+                    Some(err) => rsx! {
+                        div {
+                            class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded",
+                            role: "alert",
+                            "{err}"
+                        }
+                    },
+                    None => rsx! {}
+                }
+            }
+
+            button {
+                class: "px-4 py-2 bg-red-500 hover:bg-red-600 disabled:opacity-50 text-white rounded",
+                disabled: !confirmed,
+                onclick: move |_| {
+                    let confirm_username = confirm.read().clone();
+                    spawn(async move {
+                        match delete_account(confirm_username).await {
+                            Ok(()) => logout().await,
+                            Err(e) => error.set(Some(format!("Failed to delete account: {}", e))),
+                        }
+                    });
+                },
+
+                "Delete account"
+            }
+        }
+    }
+}
+
+#[component]
+fn AvatarForm(user: UserInfo, onsaved: EventHandler<UserInfo>) -> Element {
+    let mut error = use_signal(|| None as Option<String>);
+    let has_avatar = user.avatar_version.is_some();
+
+    rsx! {
+        div {
+            class: "flex flex-row items-center",
+
+            div {
+                class: "flex-shrink-0 w-16 h-16 mr-4",
+                Avatar { user_uuid: user.uuid, avatar_version: user.avatar_version, show_presence: false }
+            }
+
+            div {
+                class: "flex flex-col",
+
+                label {
+                    class: "bg-gray-200 hover:bg-gray-300 text-sm px-4 py-2 rounded-2xl mb-2 cursor-pointer text-center",
+
+                    "Upload avatar"
+
+                    input {
+                        class: "hidden",
+                        r#type: "file",
+                        accept: "image/png,image/jpeg,image/gif,image/webp",
+                        onchange: move |e| async move {
+                            let Some(file) = e.files().into_iter().next() else {
+                                return;
+                            };
+
+                            let bytes = match file.read_bytes().await {
+                                Ok(bytes) => bytes.to_vec(),
+                                Err(e) => {
+                                    error.set(Some(format!("Failed to read image: {}", e)));
+                                    return;
+                                }
+                            };
+
+                            match upload_avatar(bytes, "application/octet-stream".to_string()).await {
+                                Ok(updated) => {
+                                    error.set(None);
+                                    onsaved.call(updated);
+                                }
+                                Err(e) => error.set(Some(format!("Failed to upload avatar: {}", e))),
+                            }
+                        },
+                    }
+                }
+
+                { if has_avatar { rsx! {
+                    button {
+                        class: "bg-red-200 hover:bg-red-300 text-sm px-4 py-2 rounded-2xl",
+                        onclick: move |_| {
+                            spawn(async move {
+                                match remove_avatar().await {
+                                    Ok(updated) => {
+                                        error.set(None);
+                                        onsaved.call(updated);
+                                    }
+                                    Err(e) => error.set(Some(format!("Failed to remove avatar: {}", e))),
+                                }
+                            });
+                        },
+
+                        "Remove avatar"
+                    }
+                } } else { rsx! {} } }
+            }
+        }
+
+        {
+            match error() {
+                Some(err) => rsx! {
+                    div {
+                        class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded",
+                        role: "alert",
+                        "{err}"
+                    }
+                },
+                None => rsx! {}
+            }
+        }
+    }
+}
+
+#[component]
+fn ProfileForm(user: UserInfo, onsaved: EventHandler<UserInfo>) -> Element {
+    let mut username = use_signal(|| user.username.clone());
+    let mut nickname = use_signal(|| user.nickname.clone());
+    let mut error = use_signal(|| None as Option<String>);
+    let mut username_error = use_signal(|| None as Option<String>);
+    let mut nickname_error = use_signal(|| None as Option<String>);
+
+    rsx! {
+        form {
+            class: "flex flex-col",
+            onsubmit: move |e| {
+                e.prevent_default();
+                let new_username = validate_username(&username.read());
+                let new_nickname = validate_nickname(&nickname.read());
+                username_error.set(new_username.as_ref().err().map(|e| e.to_string()));
+                nickname_error.set(new_nickname.as_ref().err().map(|e| e.to_string()));
+                error.set(None);
+
+                let (Ok(new_username), Ok(new_nickname)) = (new_username, new_nickname) else {
+                    return;
+                };
+
+                spawn(async move {
+                    match update_profile(new_username, new_nickname).await {
+                        Ok(updated) => onsaved.call(updated),
+                        Err(e) => match validation_error(&e) {
+                            Some(invalid) if invalid.field == Field::Username => {
+                                username_error.set(Some(invalid.to_string()))
+                            }
+                            Some(invalid) if invalid.field == Field::Nickname => {
+                                nickname_error.set(Some(invalid.to_string()))
+                            }
+                            _ => error.set(Some(format!("Failed to update profile: {}", e))),
+                        },
+                    }
+                });
+            },
+
+            input {
+                class: "p-2 mb-2 border border-gray-300 rounded",
+                r#type: "text",
+                placeholder: "Username",
+                value: "{username}",
+                oninput: move |e| username.set(e.value()),
+            }
+
+            { if let Some(err) = username_error() { rsx! {
+                p { class: "text-xs text-red-700 mb-2", "{err}" }
+            } } else { rsx! {} } }
+
+            input {
+                class: "p-2 mb-2 border border-gray-300 rounded",
+                r#type: "text",
+                placeholder: "Nickname",
+                value: "{nickname}",
+                oninput: move |e| nickname.set(e.value()),
+            }
+
+            { if let Some(err) = nickname_error() { rsx! {
+                p { class: "text-xs text-red-700 mb-2", "{err}" }
+            } } else { rsx! {} } }
+
+            {
+                match error() {
+                    Some(err) => rsx! {
+                        div {
+                            class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mb-2",
+                            role: "alert",
+                            "{err}"
+                        }
+                    },
+                    None => rsx! {}
+                }
+            }
+
+            button {
+                class: "text-center text-white bg-blue-600 hover:bg-blue-700 rounded p-2",
+                r#type: "submit",
+
+                "Save profile"
+            }
+        }
+    }
+}
+
+#[component]
+fn StatusForm(user: UserInfo, onsaved: EventHandler<UserInfo>) -> Element {
+    let status = user.active_status(Utc::now().naive_utc()).cloned();
+    let mut bio = use_signal(|| user.bio.clone());
+    let mut emoji = use_signal(|| status.as_ref().map(|s| s.emoji.clone()).unwrap_or_default());
+    let mut text = use_signal(|| status.as_ref().map(|s| s.text.clone()).unwrap_or_default());
+    let mut expires_at = use_signal(|| status.as_ref().and_then(|s| s.expires_at));
+    let mut error = use_signal(|| None as Option<String>);
+
+    let save = move |status: Option<UserStatus>| {
+        let new_bio = bio.read().trim().to_string();
+        spawn(async move {
+            match set_status(new_bio, status).await {
+                Ok(updated) => {
+                    error.set(None);
+                    onsaved.call(updated);
+                }
+                Err(e) => error.set(Some(format!("Failed to update status: {}", e))),
+            }
+        });
+    };
+
+    rsx! {
+        form {
+            class: "flex flex-col",
+            onsubmit: move |e| {
+                e.prevent_default();
+                let new_emoji = emoji.read().trim().to_string();
+                let new_text = text.read().trim().to_string();
+                let status = if new_emoji.is_empty() && new_text.is_empty() {
+                    None
+                } else {
+                    Some(UserStatus {
+                        emoji: new_emoji,
+                        text: new_text,
+                        expires_at: *expires_at.read(),
+                    })
+                };
+                save(status);
+            },
+
+            textarea {
+                class: "p-2 mb-2 border border-gray-300 rounded resize-none",
+                rows: 3,
+                placeholder: "Bio",
+                value: "{bio}",
+                oninput: move |e| bio.set(e.value()),
+            }
+
+            div {
+                class: "flex flex-row mb-2",
+
+                input {
+                    class: "p-2 mr-2 w-16 border border-gray-300 rounded text-center",
+                    r#type: "text",
+                    placeholder: "🙂",
+                    value: "{emoji}",
+                    oninput: move |e| emoji.set(e.value()),
+                }
+
+                input {
+                    class: "p-2 flex-1 border border-gray-300 rounded",
+                    r#type: "text",
+                    placeholder: "What's your status?",
+                    value: "{text}",
+                    oninput: move |e| text.set(e.value()),
+                }
+            }
+
+            div {
+                class: "flex flex-row flex-wrap items-center mb-2",
+
+                { [("Don't clear", None), ("1 hour", Some(Duration::hours(1))), ("4 hours", Some(Duration::hours(4))), ("Today", Some(Duration::days(1)))].into_iter().map(|(label, duration)| {
+                    rsx! {
+                        button {
+                            class: "bg-gray-200 hover:bg-gray-300 text-sm px-4 py-2 rounded-2xl mr-2 mb-2",
+                            r#type: "button",
+                            onclick: move |_| {
+                                expires_at.set(duration.map(|duration| Utc::now().naive_utc() + duration));
+                            },
+
+                            "{label}"
+                        }
+                    }
+                }) }
+            }
+
+            p {
+                class: "text-xs text-gray-500 mb-2",
+                {expiry_text(*expires_at.read())}
+            }
+
+            {
+                match error() {
+                    Some(err) => rsx! {
+                        div {
+                            class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mb-2",
+                            role: "alert",
+                            "{err}"
+                        }
+                    },
+                    None => rsx! {}
+                }
+            }
+
+            button {
+                class: "text-center text-white bg-blue-600 hover:bg-blue-700 rounded p-2 mb-2",
+                r#type: "submit",
+
+                "Save status"
+            }
+
+            { if status.is_some() { rsx! {
+                button {
+                    class: "bg-red-200 hover:bg-red-300 text-sm px-4 py-2 rounded-2xl",
+                    r#type: "button",
+                    onclick: move |_| {
+                        emoji.set(String::new());
+                        text.set(String::new());
+                        expires_at.set(None);
+                        save(None);
+                    },
+
+                    "Clear status"
+                }
+            } } else { rsx! {} } }
+        }
+    }
+}
+
+fn expiry_text(expires_at: Option<NaiveDateTime>) -> String {
+    match expires_at {
+        Some(expires_at) => format!("Clears at {}", expires_at.format("%Y-%m-%d %H:%M UTC")),
+        None => "Status won't clear automatically".to_string(),
+    }
+}
+
+#[component]
+fn PrivacyForm() -> Element {
+    let mut settings = use_api_data(|| async { get_privacy().await });
+    let mut error = use_signal(|| None as Option<String>);
+
+    let Some(current) = settings.read().as_ref().copied() else {
+        return rsx! {};
+    };
+
+    let save = move |new_settings: PrivacySettings| {
+        spawn(async move {
+            match save_privacy(new_settings).await {
+                Ok(saved) => {
+                    error.set(None);
+                    settings.set(ApiData::Loaded(saved));
+                }
+                Err(e) => error.set(Some(format!("Failed to save privacy settings: {}", e))),
+            }
+        });
+    };
+
+    rsx! {
+        div {
+            class: "flex flex-col border-t border-gray-300 pt-4 space-y-2",
+
+            p { class: "text-sm font-bold", "Privacy" }
+
+            p { class: "text-sm", "Who can message me" }
+            AudiencePicker {
+                selected: current.dm,
+                onselect: move |dm| save(PrivacySettings { dm, ..current }),
+            }
+
+            p { class: "text-sm", "Who can see my last seen" }
+            AudiencePicker {
+                selected: current.last_seen,
+                onselect: move |last_seen| save(PrivacySettings { last_seen, ..current }),
+            }
+
+            label {
+                class: "text-sm",
+                input {
+                    class: "mr-2",
+                    r#type: "checkbox",
+                    checked: current.discoverable,
+                    onchange: move |e| save(PrivacySettings { discoverable: e.checked(), ..current }),
+                }
+                "Show me in user search"
+            }
+
+            {
+                match error() {
+                    Some(err) => rsx! {
+                        div {
+                            class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded",
+                            role: "alert",
+                            "{err}"
+                        }
+                    },
+                    None => rsx! {}
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn AudiencePicker(selected: PrivacyAudience, onselect: EventHandler<PrivacyAudience>) -> Element {
+    rsx! {
+        div {
+            class: "flex flex-row flex-wrap",
+
+            { [("Everyone", PrivacyAudience::Everyone), ("People in my groups", PrivacyAudience::SharedGroup), ("Nobody", PrivacyAudience::Nobody)].into_iter().map(|(label, audience)| {
+                let color = if audience == selected { "bg-blue-200 hover:bg-blue-300" } else { "bg-gray-200 hover:bg-gray-300" };
+                rsx! {
+                    button {
+                        class: "{color} text-sm px-4 py-2 rounded-2xl mr-2 mb-2",
+                        onclick: move |_| onselect.call(audience),
+
+                        "{label}"
+                    }
+                }
+            }) }
+        }
+    }
+}
+
+#[component]
+fn AccessTokensForm() -> Element {
+    let mut tokens = use_api_data(|| async { list_access_tokens().await });
+    let mut name = use_signal(String::new);
+    let mut scopes = use_signal(|| vec![TokenScope::ReadMessages]);
+    let mut lifetime = use_signal(|| Some(Duration::days(30)));
+    let mut created = use_signal(|| None as Option<String>);
+    let mut name_error = use_signal(|| None as Option<String>);
+    let mut error = use_signal(|| None as Option<String>);
+
+    let Some(current) = tokens.read().as_ref().cloned() else {
+        return rsx! {};
+    };
+
+    let onsubmit = move |e: Event<FormData>| {
+        e.prevent_default();
+
+        let new_name = match validate_token_name(&name.read()) {
+            Ok(new_name) => new_name,
+            Err(e) => {
+                name_error.set(Some(e.to_string()));
+                return;
+            }
+        };
+        name_error.set(None);
+
+        let new_scopes = scopes.read().clone();
+        let expires_at = lifetime
+            .read()
+            .map(|lifetime| Utc::now().naive_utc() + lifetime);
+        spawn(async move {
+            match create_access_token(new_name, new_scopes, expires_at).await {
+                Ok(response) => {
+                    error.set(None);
+                    name.set(String::new());
+                    created.set(Some(response.token));
+                    let mut updated = tokens.read().as_ref().cloned().unwrap_or_default();
+                    updated.insert(0, response.info);
+                    tokens.set(ApiData::Loaded(updated));
+                }
+                Err(e) => match validation_error(&e) {
+                    Some(invalid) if invalid.field == Field::TokenName => {
+                        name_error.set(Some(invalid.to_string()))
+                    }
+                    Some(invalid) => error.set(Some(invalid.to_string())),
+                    None => error.set(Some(format!("Failed to create access token: {}", e))),
+                },
+            }
+        });
+    };
+
+    let revoke = move |uuid: Uuid| {
+        spawn(async move {
+            match revoke_access_token(uuid).await {
+                Ok(()) => {
+                    error.set(None);
+                    if let Ok(updated) = list_access_tokens().await.log_error() {
+                        tokens.set(ApiData::Loaded(updated));
+                    }
+                }
+                Err(e) => error.set(Some(format!("Failed to revoke access token: {}", e))),
+            }
+        });
+    };
+
+    rsx! {
+        div {
+            class: "flex flex-col border-t border-gray-300 pt-4 space-y-2",
+
+            p { class: "text-sm font-bold", "Access tokens" }
+
+            p {
+                class: "text-xs text-gray-500",
+                "Tokens let scripts call the API with an Authorization: Bearer header."
+            }
+
+            { current.iter().map(|token| {
+                let uuid = token.uuid;
+                let scope_labels = token.scopes.iter().map(|scope| scope.label()).collect::<Vec<_>>().join(", ");
+                let last_used = token.last_used_at.map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string()).unwrap_or_else(|| "never".to_string());
+                let expires = token.expires_at.map(|t| t.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "never".to_string());
+                rsx! {
+                    div {
+                        key: "{uuid}",
+                        class: "flex flex-row items-center",
+
+                        div {
+                            class: "flex flex-col flex-1",
+                            p { class: "text-sm", "{token.name}" }
+                            p { class: "text-xs text-gray-500", "{scope_labels}" }
+                            p { class: "text-xs text-gray-500", "Last used {last_used}, expires {expires}" }
+                        }
+
+                        button {
+                            class: "bg-red-200 hover:bg-red-300 text-sm px-4 py-2 rounded-2xl",
+                            onclick: move |_| revoke(uuid),
+
+                            "Revoke"
+                        }
+                    }
+                }
+            }) }
+
+            { if let Some(token) = created() { rsx! {
+                div {
+                    class: "bg-green-100 border border-green-400 text-green-800 px-4 py-3 rounded break-all",
+                    p { class: "text-xs mb-1", "Copy this token now, it won't be shown again:" }
+                    p { class: "text-sm font-mono", "{token}" }
+                }
+            } } else { rsx! {} } }
+
+            form {
+                class: "flex flex-col",
+                onsubmit: onsubmit,
+
+                input {
+                    class: "p-2 mb-2 border border-gray-300 rounded",
+                    r#type: "text",
+                    placeholder: "Token name",
+                    value: "{name}",
+                    oninput: move |e| name.set(e.value()),
+                }
+
+                { if let Some(err) = name_error() { rsx! {
+                    p { class: "text-xs text-red-700 -mt-1 mb-2", "{err}" }
+                } } else { rsx! {} } }
+
+                div {
+                    class: "flex flex-row flex-wrap",
+
+                    { TokenScope::ALL.into_iter().map(|scope| {
+                        let selected = scopes.read().contains(&scope);
+                        let color = if selected { "bg-blue-200 hover:bg-blue-300" } else { "bg-gray-200 hover:bg-gray-300" };
+                        rsx! {
+                            button {
+                                class: "{color} text-sm px-4 py-2 rounded-2xl mr-2 mb-2",
+                                r#type: "button",
+                                onclick: move |_| {
+                                    let mut selected_scopes = scopes.write();
+                                    if selected {
+                                        selected_scopes.retain(|s| *s != scope);
+                                    } else {
+                                        selected_scopes.push(scope);
+                                    }
+                                },
+
+                                "{scope.label()}"
+                            }
+                        }
+                    }) }
+                }
+
+                div {
+                    class: "flex flex-row flex-wrap",
+
+                    { [("30 days", Some(Duration::days(30))), ("90 days", Some(Duration::days(90))), ("No expiry", None)].into_iter().map(|(label, duration)| {
+                        let color = if *lifetime.read() == duration { "bg-blue-200 hover:bg-blue-300" } else { "bg-gray-200 hover:bg-gray-300" };
+                        rsx! {
+                            button {
+                                class: "{color} text-sm px-4 py-2 rounded-2xl mr-2 mb-2",
+                                r#type: "button",
+                                onclick: move |_| lifetime.set(duration),
+
+                                "{label}"
+                            }
+                        }
+                    }) }
+                }
+
+                {
+                    match error() {
+                        Some(err) => rsx! {
+                            div {
+                                class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mb-2",
+                                role: "alert",
+                                "{err}"
+                            }
+                        },
+                        None => rsx! {}
+                    }
+                }
+
+                button {
+                    class: "text-center text-white bg-blue-600 hover:bg-blue-700 rounded p-2",
+                    r#type: "submit",
+                    disabled: scopes.read().is_empty(),
+
+                    "Create token"
+                }
+            }
         }
     }
 }
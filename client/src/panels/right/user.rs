@@ -1,9 +1,15 @@
+use chrono::Utc;
 use dioxus::prelude::*;
+use uuid::Uuid;
 
 use crate::{
     Route,
-    backend::{get_username, verify_private_chat},
+    backend::{
+        block_user, find_contact, get_username, list_blocked_users, reload_contacts, set_contact,
+        unblock_user, unset_contact, verify_private_chat,
+    },
     components::{Avatar, Header, HeaderButtonBack, HeaderText, Spinner},
+    presence::{last_seen_text, load_presence},
 };
 use utils::{LogError, data::UserInfo};
 
@@ -11,7 +17,11 @@ use utils::{LogError, data::UserInfo};
 pub enum UserState {
     Uninitialized,
     Loading,
-    Loaded { username: String, user: UserInfo },
+    Loaded {
+        username: String,
+        user: UserInfo,
+        blocked: bool,
+    },
 }
 
 #[component]
@@ -31,12 +41,29 @@ pub fn RightUser(username: String) -> Element {
             *state.write() = UserState::Loading;
 
             spawn(async move {
-                let user = get_username(username.clone())
-                    .await
+                let (user_res, blocked_res) =
+                    futures::join!(get_username(username.clone()), list_blocked_users());
+
+                let user = user_res.log_error().expect("Failed to fetch user profile");
+                if user.username != username {
+                    navigator.replace(Route::ViewUser {
+                        username: user.username.clone(),
+                    });
+                }
+
+                let blocked = blocked_res
                     .log_error()
-                    .expect("Failed to fetch user profile");
+                    .expect("Failed to fetch blocked users")
+                    .iter()
+                    .any(|u| u.uuid == user.uuid);
 
-                *state.write() = UserState::Loaded { username, user }
+                spawn(load_presence(vec![user.uuid]));
+
+                *state.write() = UserState::Loaded {
+                    username: user.username.clone(),
+                    user,
+                    blocked,
+                }
             });
         }
 
@@ -47,7 +74,10 @@ pub fn RightUser(username: String) -> Element {
         UserState::Uninitialized | UserState::Loading => {
             rsx! { Spinner {} }
         }
-        UserState::Loaded { user, .. } => {
+        UserState::Loaded { user, blocked, .. } => {
+            let user_uuid = user.uuid;
+            let block_label = if blocked { "Unblock" } else { "Block" };
+
             rsx! {
                 Header {
                     left: rsx! { HeaderButtonBack {
@@ -63,7 +93,7 @@ pub fn RightUser(username: String) -> Element {
                     class: "flex flex-col items-center p-6",
 
                     div {
-                        Avatar { email_hash: user.email_hash.clone() },
+                        Avatar { user_uuid: user.uuid, avatar_version: user.avatar_version },
                     }
 
                     div {
@@ -78,13 +108,24 @@ pub fn RightUser(username: String) -> Element {
                             class: "text-s",
                             "@{user.username}"
                         }
+
+                        { if let Some(status) = user.active_status(Utc::now().naive_utc()) { rsx! {
+                            p { class: "text-sm", "{status.label()}" }
+                        } } else { rsx! {} } }
+
+                        { if let Some(last_seen) = last_seen_text(user.uuid) { rsx! {
+                            p { class: "text-xs text-gray-500", "{last_seen}" }
+                        } } else { rsx! {} } }
+
+                        { if !user.bio.is_empty() { rsx! {
+                            p { class: "text-sm whitespace-pre-wrap break-words mt-2", "{user.bio}" }
+                        } } else { rsx! {} } }
                     }
 
                     div {
                         button {
                             class: "text-white bg-blue-700 hover:bg-blue-800 focus:ring-4 focus:ring-blue-300 font-medium rounded-lg text-sm px-5 py-2.5 me-2 mb-2",
                             onclick: move |_| {
-                                let user_uuid = user.uuid;
                                 spawn(async move {
                                     match verify_private_chat(user_uuid).await {
                                         Ok(chat_uuid) => {
@@ -98,9 +139,86 @@ pub fn RightUser(username: String) -> Element {
                             },
                             "Message"
                         }
+
+                        button {
+                            class: "text-white bg-red-600 hover:bg-red-700 focus:ring-4 focus:ring-red-300 font-medium rounded-lg text-sm px-5 py-2.5 me-2 mb-2",
+                            onclick: move |_| {
+                                spawn(async move {
+                                    let result = if blocked {
+                                        unblock_user(user_uuid).await
+                                    } else {
+                                        block_user(user_uuid).await
+                                    };
+
+                                    if result.log_error().is_ok()
+                                        && let UserState::Loaded { blocked, .. } = &mut *state.write()
+                                    {
+                                        *blocked = !*blocked;
+                                    }
+                                });
+                            },
+                            "{block_label}"
+                        }
                     }
+
+                    ContactControls { user_uuid }
                 }
             }
         }
     }
 }
+
+#[component]
+fn ContactControls(user_uuid: Uuid) -> Element {
+    let mut nickname = use_signal(String::new);
+
+    use_future(reload_contacts);
+    use_effect(move || {
+        let saved = find_contact(user_uuid).and_then(|contact| contact.nickname);
+        nickname.set(saved.unwrap_or_default());
+    });
+
+    let is_contact = find_contact(user_uuid).is_some();
+    let save_label = if is_contact {
+        "Save nickname"
+    } else {
+        "Add to contacts"
+    };
+
+    rsx! {
+        div {
+            class: "flex flex-col items-center mt-2",
+
+            input {
+                class: "p-2 mb-2 border border-gray-300 rounded",
+                r#type: "text",
+                placeholder: "Contact nickname (optional)",
+                value: "{nickname}",
+                oninput: move |e| nickname.set(e.value()),
+            }
+
+            div {
+                button {
+                    class: "bg-gray-200 hover:bg-gray-300 text-sm px-4 py-2 rounded-2xl mr-2",
+                    onclick: move |_| {
+                        let nickname = Some(nickname.read().trim().to_string()).filter(|n| !n.is_empty());
+                        spawn(set_contact(user_uuid, nickname));
+                    },
+
+                    "{save_label}"
+                }
+
+                { if is_contact { rsx! {
+                    button {
+                        class: "bg-red-200 hover:bg-red-300 text-sm px-4 py-2 rounded-2xl mr-2",
+                        onclick: move |_| {
+                            spawn(unset_contact(user_uuid));
+                        },
+
+                        "Remove from contacts"
+                    }
+                } } else { rsx! {} } }
+            }
+        }
+    }
+}
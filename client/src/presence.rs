@@ -0,0 +1,67 @@
+use chrono::{NaiveDateTime, Utc};
+use dioxus::prelude::*;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::backend::{get_presence, heartbeat};
+use utils::{LogError, data::Presence};
+
+const HEARTBEAT_INTERVAL_MS: u32 = 30_000;
+
+pub static PRESENCE: GlobalSignal<HashMap<Uuid, Presence>> = GlobalSignal::new(HashMap::new);
+
+pub async fn run_heartbeat() {
+    loop {
+        heartbeat().await.log_error().ok();
+        gloo_timers::future::TimeoutFuture::new(HEARTBEAT_INTERVAL_MS).await;
+    }
+}
+
+pub async fn load_presence(user_uuids: Vec<Uuid>) {
+    if user_uuids.is_empty() {
+        return;
+    }
+
+    if let Ok(presences) = get_presence(user_uuids).await.log_error() {
+        let mut presence_map = PRESENCE.write();
+        for presence in presences {
+            presence_map.insert(presence.user_uuid, presence);
+        }
+    }
+}
+
+pub fn set_presence(presence: Presence) {
+    PRESENCE.write().insert(presence.user_uuid, presence);
+}
+
+pub fn is_online(user_uuid: Uuid) -> bool {
+    PRESENCE
+        .read()
+        .get(&user_uuid)
+        .is_some_and(|presence| presence.online)
+}
+
+pub fn last_seen_text(user_uuid: Uuid) -> Option<String> {
+    let presence = PRESENCE.read().get(&user_uuid).copied()?;
+    if presence.online {
+        return Some("online".to_string());
+    }
+
+    Some(match presence.last_seen_at {
+        Some(last_seen_at) => format!("last seen {}", relative_time(last_seen_at)),
+        None => "last seen a long time ago".to_string(),
+    })
+}
+
+fn relative_time(time: NaiveDateTime) -> String {
+    let elapsed = Utc::now().naive_utc() - time;
+    if elapsed.num_minutes() < 1 {
+        "just now".to_string()
+    } else if elapsed.num_minutes() < 60 {
+        format!("{} min ago", elapsed.num_minutes())
+    } else if elapsed.num_hours() < 24 {
+        format!("{} h ago", elapsed.num_hours())
+    } else {
+        time.format("on %b %-d").to_string()
+    }
+}
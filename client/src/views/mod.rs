@@ -16,6 +16,7 @@ use crate::{
     centrifugo::{CentrifugoClient, CentrifugoContext},
     components::NavBar,
     panels::{LayoutContext, PanelLayout},
+    presence::run_heartbeat,
 };
 use std::rc::Rc;
 
@@ -42,6 +43,8 @@ pub fn View(view_right: bool, left: Element, right: Element) -> Element {
         client: centrifugo.clone(),
     });
 
+    use_future(run_heartbeat);
+
     use_effect({
         move || {
             let mut layout = use_context::<LayoutContext>().layout;
@@ -5,6 +5,27 @@ mod m20251011_135939_chats;
 mod m20251011_140310_chat_members;
 mod m20251011_141157_messages;
 mod m20251018_043854_message_reply;
+mod m20261019_120000_chat_preferences;
+mod m20261019_140000_chat_kinds;
+mod m20261019_160000_messages_chat_index;
+mod m20261019_180000_user_blocks;
+mod m20261019_200000_unique_private_chats;
+mod m20261019_220000_reports;
+mod m20261019_230000_slow_mode;
+mod m20261019_233000_chat_folders;
+mod m20261019_234500_username_redirects;
+mod m20261019_235500_user_search;
+mod m20261020_000000_last_seen;
+mod m20261020_010000_account_deletion;
+mod m20261020_020000_avatars;
+mod m20261020_030000_bio_status;
+mod m20261020_040000_privacy_settings;
+mod m20261020_050000_contacts;
+mod m20261020_060000_unique_usernames;
+mod m20261020_070000_sessions;
+mod m20261020_080000_access_tokens;
+mod m20261020_090000_folder_include_all;
+mod m20261020_100000_refresh_token_reuse;
 
 pub struct Migrator;
 
@@ -17,6 +38,27 @@ impl MigratorTrait for Migrator {
             Box::new(m20251011_140310_chat_members::Migration),
             Box::new(m20251011_141157_messages::Migration),
             Box::new(m20251018_043854_message_reply::Migration),
+            Box::new(m20261019_120000_chat_preferences::Migration),
+            Box::new(m20261019_140000_chat_kinds::Migration),
+            Box::new(m20261019_160000_messages_chat_index::Migration),
+            Box::new(m20261019_180000_user_blocks::Migration),
+            Box::new(m20261019_200000_unique_private_chats::Migration),
+            Box::new(m20261019_220000_reports::Migration),
+            Box::new(m20261019_230000_slow_mode::Migration),
+            Box::new(m20261019_233000_chat_folders::Migration),
+            Box::new(m20261019_234500_username_redirects::Migration),
+            Box::new(m20261019_235500_user_search::Migration),
+            Box::new(m20261020_000000_last_seen::Migration),
+            Box::new(m20261020_010000_account_deletion::Migration),
+            Box::new(m20261020_020000_avatars::Migration),
+            Box::new(m20261020_030000_bio_status::Migration),
+            Box::new(m20261020_040000_privacy_settings::Migration),
+            Box::new(m20261020_050000_contacts::Migration),
+            Box::new(m20261020_060000_unique_usernames::Migration),
+            Box::new(m20261020_070000_sessions::Migration),
+            Box::new(m20261020_080000_access_tokens::Migration),
+            Box::new(m20261020_090000_folder_include_all::Migration),
+            Box::new(m20261020_100000_refresh_token_reuse::Migration),
         ]
     }
 }
@@ -9,6 +9,18 @@ pub enum Users {
     Username,
     Nickname,
     CreatedAt,
+    IsModerator,
+    BannedAt,
+    LastSeenAt,
+    DeletedAt,
+    AvatarUpdatedAt,
+    Bio,
+    StatusEmoji,
+    StatusText,
+    StatusExpiresAt,
+    DmPrivacy,
+    LastSeenPrivacy,
+    Discoverable,
 }
 
 #[derive(DeriveMigrationName)]
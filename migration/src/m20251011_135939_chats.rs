@@ -6,7 +6,11 @@ pub enum Chats {
     Uuid,
     Name,
     IsGroup,
+    Kind,
     CreatedAt,
+    PrivateFirstUuid,
+    PrivateSecondUuid,
+    SlowModeSeconds,
 }
 
 #[derive(DeriveMigrationName)]
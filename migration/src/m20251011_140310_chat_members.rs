@@ -9,6 +9,10 @@ pub enum ChatMembers {
     ChatUuid,
     UserUuid,
     JoinedAt,
+    MutedUntil,
+    Pinned,
+    Archived,
+    Role,
 }
 
 #[derive(DeriveMigrationName)]
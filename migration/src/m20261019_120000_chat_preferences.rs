@@ -0,0 +1,35 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20251011_140310_chat_members::ChatMembers;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatMembers::Table)
+                    .add_column(timestamp_null(ChatMembers::MutedUntil))
+                    .add_column(integer_null(ChatMembers::Pinned))
+                    .add_column(boolean(ChatMembers::Archived).not_null().default(false))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatMembers::Table)
+                    .drop_column(ChatMembers::MutedUntil)
+                    .drop_column(ChatMembers::Pinned)
+                    .drop_column(ChatMembers::Archived)
+                    .to_owned(),
+            )
+            .await
+    }
+}
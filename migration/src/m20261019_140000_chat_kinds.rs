@@ -0,0 +1,169 @@
+use sea_orm_migration::{
+    prelude::{extension::postgres::Type, *},
+    schema::*,
+};
+
+use crate::m20251011_135939_chats::Chats;
+use crate::m20251011_140310_chat_members::ChatMembers;
+
+#[derive(DeriveIden)]
+pub enum ChatKind {
+    #[sea_orm(iden = "chat_kind")]
+    Enum,
+    Private,
+    Group,
+    Broadcast,
+}
+
+#[derive(DeriveIden)]
+pub enum ChatRole {
+    #[sea_orm(iden = "chat_role")]
+    Enum,
+    Owner,
+    Admin,
+    Member,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(ChatKind::Enum)
+                    .values([ChatKind::Private, ChatKind::Group, ChatKind::Broadcast])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(ChatRole::Enum)
+                    .values([ChatRole::Owner, ChatRole::Admin, ChatRole::Member])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Chats::Table)
+                    .add_column(
+                        enumeration(
+                            Chats::Kind,
+                            ChatKind::Enum,
+                            [ChatKind::Private, ChatKind::Group, ChatKind::Broadcast],
+                        )
+                        .not_null()
+                        .default(Expr::cust("'group'")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Chats::Table)
+                    .value(Chats::Kind, Expr::cust("'private'"))
+                    .and_where(Expr::col(Chats::IsGroup).eq(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Chats::Table)
+                    .drop_column(Chats::IsGroup)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatMembers::Table)
+                    .add_column(
+                        enumeration(
+                            ChatMembers::Role,
+                            ChatRole::Enum,
+                            [ChatRole::Owner, ChatRole::Admin, ChatRole::Member],
+                        )
+                        .not_null()
+                        .default(Expr::cust("'member'")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE chat_members SET role = 'owner'
+                FROM (
+                    SELECT DISTINCT ON (chat_members.chat_uuid) chat_members.chat_uuid, chat_members.user_uuid
+                    FROM chat_members
+                    JOIN chats ON chats.uuid = chat_members.chat_uuid
+                    WHERE chats.kind <> 'private'
+                    ORDER BY chat_members.chat_uuid, chat_members.joined_at, chat_members.user_uuid
+                ) AS owners
+                WHERE chat_members.chat_uuid = owners.chat_uuid
+                AND chat_members.user_uuid = owners.user_uuid",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatMembers::Table)
+                    .drop_column(ChatMembers::Role)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Chats::Table)
+                    .add_column(boolean(Chats::IsGroup).not_null().default(true))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Chats::Table)
+                    .value(Chats::IsGroup, false)
+                    .and_where(Expr::col(Chats::Kind).eq(Expr::cust("'private'")))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Chats::Table)
+                    .drop_column(Chats::Kind)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(ChatRole::Enum).to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(ChatKind::Enum).to_owned())
+            .await
+    }
+}
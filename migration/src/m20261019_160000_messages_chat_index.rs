@@ -0,0 +1,33 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20251011_141157_messages::Messages;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-messages-chat-created")
+                    .table(Messages::Table)
+                    .col(Messages::ChatUuid)
+                    .col((Messages::CreatedAt, IndexOrder::Desc))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-messages-chat-created")
+                    .table(Messages::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20251011_133950_users::Users;
+
+#[derive(DeriveIden)]
+pub enum UserBlocks {
+    Table,
+    BlockerUuid,
+    BlockedUuid,
+    CreatedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserBlocks::Table)
+                    .if_not_exists()
+                    .col(uuid(UserBlocks::BlockerUuid).not_null())
+                    .col(uuid(UserBlocks::BlockedUuid).not_null())
+                    .col(
+                        timestamp(UserBlocks::CreatedAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name("pk-user_blocks")
+                            .col(UserBlocks::BlockerUuid)
+                            .col(UserBlocks::BlockedUuid),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-user_blocks-blocker")
+                            .from(UserBlocks::Table, UserBlocks::BlockerUuid)
+                            .to(Users::Table, Users::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-user_blocks-blocked")
+                            .from(UserBlocks::Table, UserBlocks::BlockedUuid)
+                            .to(Users::Table, Users::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserBlocks::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,98 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20251011_135939_chats::Chats;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Chats::Table)
+                    .add_column(uuid_null(Chats::PrivateFirstUuid))
+                    .add_column(uuid_null(Chats::PrivateSecondUuid))
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            "UPDATE chats SET private_first_uuid = pairs.members[1], private_second_uuid = pairs.members[2]
+            FROM (
+                SELECT chat_uuid, array_agg(user_uuid ORDER BY user_uuid) AS members
+                FROM chat_members
+                GROUP BY chat_uuid
+                HAVING count(*) = 2
+            ) AS pairs
+            WHERE chats.uuid = pairs.chat_uuid AND chats.kind = 'private'",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "CREATE TEMPORARY TABLE duplicate_private_chats AS
+            SELECT uuid, keeper FROM (
+                SELECT uuid, first_value(uuid) OVER (
+                    PARTITION BY private_first_uuid, private_second_uuid
+                    ORDER BY created_at, uuid
+                ) AS keeper
+                FROM chats
+                WHERE private_first_uuid IS NOT NULL
+            ) AS ranked
+            WHERE uuid <> keeper",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "UPDATE messages SET chat_uuid = duplicate_private_chats.keeper
+            FROM duplicate_private_chats
+            WHERE messages.chat_uuid = duplicate_private_chats.uuid",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "DELETE FROM chats USING duplicate_private_chats
+            WHERE chats.uuid = duplicate_private_chats.uuid",
+        )
+        .await?;
+
+        db.execute_unprepared("DROP TABLE duplicate_private_chats")
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-chats-private-pair")
+                    .table(Chats::Table)
+                    .col(Chats::PrivateFirstUuid)
+                    .col(Chats::PrivateSecondUuid)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-chats-private-pair")
+                    .table(Chats::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Chats::Table)
+                    .drop_column(Chats::PrivateFirstUuid)
+                    .drop_column(Chats::PrivateSecondUuid)
+                    .to_owned(),
+            )
+            .await
+    }
+}
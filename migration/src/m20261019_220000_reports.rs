@@ -0,0 +1,220 @@
+use sea_orm_migration::{
+    prelude::{extension::postgres::Type, *},
+    schema::*,
+};
+
+use crate::m20251011_133950_users::Users;
+use crate::m20251011_135939_chats::Chats;
+use crate::m20251011_141157_messages::Messages;
+
+#[derive(DeriveIden)]
+pub enum ModerationAction {
+    #[sea_orm(iden = "moderation_action")]
+    Enum,
+    Dismiss,
+    DeleteMessage,
+    RemoveFromGroup,
+    BanUser,
+}
+
+#[derive(DeriveIden)]
+pub enum Reports {
+    Table,
+    Uuid,
+    MessageUuid,
+    ReporterUuid,
+    Reason,
+    CreatedAt,
+    ResolvedAt,
+    ResolvedBy,
+    Action,
+}
+
+#[derive(DeriveIden)]
+pub enum ModerationActions {
+    Table,
+    Uuid,
+    ModeratorUuid,
+    ReportUuid,
+    Action,
+    TargetUserUuid,
+    TargetMessageUuid,
+    ChatUuid,
+    CreatedAt,
+}
+
+fn moderation_action_values() -> [ModerationAction; 4] {
+    [
+        ModerationAction::Dismiss,
+        ModerationAction::DeleteMessage,
+        ModerationAction::RemoveFromGroup,
+        ModerationAction::BanUser,
+    ]
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(boolean(Users::IsModerator).not_null().default(false))
+                    .add_column(timestamp_null(Users::BannedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(ModerationAction::Enum)
+                    .values(moderation_action_values())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Reports::Table)
+                    .if_not_exists()
+                    .col(
+                        uuid(Reports::Uuid)
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("uuid_generate_v4()")),
+                    )
+                    .col(uuid(Reports::MessageUuid).not_null())
+                    .col(uuid(Reports::ReporterUuid).not_null())
+                    .col(text(Reports::Reason).not_null())
+                    .col(
+                        timestamp(Reports::CreatedAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(timestamp_null(Reports::ResolvedAt))
+                    .col(uuid_null(Reports::ResolvedBy))
+                    .col(enumeration_null(
+                        Reports::Action,
+                        ModerationAction::Enum,
+                        moderation_action_values(),
+                    ))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-reports-message")
+                            .from(Reports::Table, Reports::MessageUuid)
+                            .to(Messages::Table, Messages::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-reports-reporter")
+                            .from(Reports::Table, Reports::ReporterUuid)
+                            .to(Users::Table, Users::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-reports-resolved_by")
+                            .from(Reports::Table, Reports::ResolvedBy)
+                            .to(Users::Table, Users::Uuid)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ModerationActions::Table)
+                    .if_not_exists()
+                    .col(
+                        uuid(ModerationActions::Uuid)
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("uuid_generate_v4()")),
+                    )
+                    .col(uuid(ModerationActions::ModeratorUuid).not_null())
+                    .col(uuid_null(ModerationActions::ReportUuid))
+                    .col(enumeration(
+                        ModerationActions::Action,
+                        ModerationAction::Enum,
+                        moderation_action_values(),
+                    ))
+                    .col(uuid(ModerationActions::TargetUserUuid).not_null())
+                    .col(uuid_null(ModerationActions::TargetMessageUuid))
+                    .col(uuid_null(ModerationActions::ChatUuid))
+                    .col(
+                        timestamp(ModerationActions::CreatedAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-moderation_actions-moderator")
+                            .from(ModerationActions::Table, ModerationActions::ModeratorUuid)
+                            .to(Users::Table, Users::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-moderation_actions-report")
+                            .from(ModerationActions::Table, ModerationActions::ReportUuid)
+                            .to(Reports::Table, Reports::Uuid)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-moderation_actions-target_user")
+                            .from(ModerationActions::Table, ModerationActions::TargetUserUuid)
+                            .to(Users::Table, Users::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-moderation_actions-chat")
+                            .from(ModerationActions::Table, ModerationActions::ChatUuid)
+                            .to(Chats::Table, Chats::Uuid)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ModerationActions::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Reports::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(ModerationAction::Enum).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::IsModerator)
+                    .drop_column(Users::BannedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
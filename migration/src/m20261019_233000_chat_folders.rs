@@ -0,0 +1,110 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20251011_133950_users::Users;
+use crate::m20251011_135939_chats::Chats;
+
+#[derive(DeriveIden)]
+pub enum ChatFolders {
+    Table,
+    Uuid,
+    UserUuid,
+    Name,
+    Position,
+    GroupsOnly,
+    UnreadOnly,
+    ExcludeMuted,
+    CreatedAt,
+    IncludeAll,
+}
+
+#[derive(DeriveIden)]
+pub enum ChatFolderChats {
+    Table,
+    FolderUuid,
+    ChatUuid,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChatFolders::Table)
+                    .if_not_exists()
+                    .col(
+                        uuid(ChatFolders::Uuid)
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("uuid_generate_v4()")),
+                    )
+                    .col(uuid(ChatFolders::UserUuid).not_null())
+                    .col(text(ChatFolders::Name).not_null())
+                    .col(integer(ChatFolders::Position).not_null().default(0))
+                    .col(boolean(ChatFolders::GroupsOnly).not_null().default(false))
+                    .col(boolean(ChatFolders::UnreadOnly).not_null().default(false))
+                    .col(boolean(ChatFolders::ExcludeMuted).not_null().default(false))
+                    .col(
+                        timestamp(ChatFolders::CreatedAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-chat_folders-user")
+                            .from(ChatFolders::Table, ChatFolders::UserUuid)
+                            .to(Users::Table, Users::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChatFolderChats::Table)
+                    .if_not_exists()
+                    .col(uuid(ChatFolderChats::FolderUuid).not_null())
+                    .col(uuid(ChatFolderChats::ChatUuid).not_null())
+                    .primary_key(
+                        Index::create()
+                            .name("pk-chat_folder_chats")
+                            .col(ChatFolderChats::FolderUuid)
+                            .col(ChatFolderChats::ChatUuid),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-chat_folder_chats-folder")
+                            .from(ChatFolderChats::Table, ChatFolderChats::FolderUuid)
+                            .to(ChatFolders::Table, ChatFolders::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-chat_folder_chats-chat")
+                            .from(ChatFolderChats::Table, ChatFolderChats::ChatUuid)
+                            .to(Chats::Table, Chats::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChatFolderChats::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ChatFolders::Table).to_owned())
+            .await
+    }
+}
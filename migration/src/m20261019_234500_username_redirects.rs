@@ -0,0 +1,49 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20251011_133950_users::Users;
+
+#[derive(DeriveIden)]
+pub enum UsernameRedirects {
+    Table,
+    Username,
+    UserUuid,
+    CreatedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UsernameRedirects::Table)
+                    .if_not_exists()
+                    .col(text(UsernameRedirects::Username).not_null().primary_key())
+                    .col(uuid(UsernameRedirects::UserUuid).not_null())
+                    .col(
+                        timestamp(UsernameRedirects::CreatedAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-username_redirects-user")
+                            .from(UsernameRedirects::Table, UsernameRedirects::UserUuid)
+                            .to(Users::Table, Users::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UsernameRedirects::Table).to_owned())
+            .await
+    }
+}
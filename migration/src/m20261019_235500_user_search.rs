@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared("CREATE EXTENSION IF NOT EXISTS pg_trgm")
+            .await?;
+        db.execute_unprepared(
+            "CREATE INDEX IF NOT EXISTS \"idx-users-username-trgm\" \
+             ON users USING gin (username gin_trgm_ops)",
+        )
+        .await?;
+        db.execute_unprepared(
+            "CREATE INDEX IF NOT EXISTS \"idx-users-nickname-trgm\" \
+             ON users USING gin (nickname gin_trgm_ops)",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared("DROP INDEX IF EXISTS \"idx-users-nickname-trgm\"")
+            .await?;
+        db.execute_unprepared("DROP INDEX IF EXISTS \"idx-users-username-trgm\"")
+            .await?;
+
+        Ok(())
+    }
+}
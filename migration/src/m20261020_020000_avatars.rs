@@ -0,0 +1,69 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20251011_133950_users::Users;
+
+#[derive(DeriveIden)]
+pub enum Avatars {
+    Table,
+    UserUuid,
+    Size,
+    Data,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(timestamp_null(Users::AvatarUpdatedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Avatars::Table)
+                    .if_not_exists()
+                    .col(uuid(Avatars::UserUuid).not_null())
+                    .col(integer(Avatars::Size).not_null())
+                    .col(blob(Avatars::Data).not_null())
+                    .primary_key(
+                        Index::create()
+                            .name("pk-avatars")
+                            .col(Avatars::UserUuid)
+                            .col(Avatars::Size),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-avatars-user")
+                            .from(Avatars::Table, Avatars::UserUuid)
+                            .to(Users::Table, Users::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Avatars::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::AvatarUpdatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,37 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20251011_133950_users::Users;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(text(Users::Bio).default(""))
+                    .add_column(text_null(Users::StatusEmoji))
+                    .add_column(text_null(Users::StatusText))
+                    .add_column(timestamp_null(Users::StatusExpiresAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::Bio)
+                    .drop_column(Users::StatusEmoji)
+                    .drop_column(Users::StatusText)
+                    .drop_column(Users::StatusExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,84 @@
+use sea_orm_migration::{
+    prelude::{extension::postgres::Type, *},
+    schema::*,
+};
+
+use crate::m20251011_133950_users::Users;
+
+#[derive(DeriveIden)]
+pub enum PrivacyAudience {
+    #[sea_orm(iden = "privacy_audience")]
+    Enum,
+    Everyone,
+    SharedGroup,
+    Nobody,
+}
+
+fn privacy_audience_values() -> [PrivacyAudience; 3] {
+    [
+        PrivacyAudience::Everyone,
+        PrivacyAudience::SharedGroup,
+        PrivacyAudience::Nobody,
+    ]
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(PrivacyAudience::Enum)
+                    .values(privacy_audience_values())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(
+                        enumeration(
+                            Users::DmPrivacy,
+                            PrivacyAudience::Enum,
+                            privacy_audience_values(),
+                        )
+                        .not_null()
+                        .default(Expr::cust("'everyone'")),
+                    )
+                    .add_column(
+                        enumeration(
+                            Users::LastSeenPrivacy,
+                            PrivacyAudience::Enum,
+                            privacy_audience_values(),
+                        )
+                        .not_null()
+                        .default(Expr::cust("'everyone'")),
+                    )
+                    .add_column(boolean(Users::Discoverable).default(true))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::DmPrivacy)
+                    .drop_column(Users::LastSeenPrivacy)
+                    .drop_column(Users::Discoverable)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(PrivacyAudience::Enum).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,65 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20251011_133950_users::Users;
+
+#[derive(DeriveIden)]
+pub enum Contacts {
+    Table,
+    OwnerUuid,
+    ContactUuid,
+    Nickname,
+    CreatedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Contacts::Table)
+                    .if_not_exists()
+                    .col(uuid(Contacts::OwnerUuid).not_null())
+                    .col(uuid(Contacts::ContactUuid).not_null())
+                    .col(text_null(Contacts::Nickname))
+                    .col(
+                        timestamp(Contacts::CreatedAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name("pk-contacts")
+                            .col(Contacts::OwnerUuid)
+                            .col(Contacts::ContactUuid),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-contacts-owner")
+                            .from(Contacts::Table, Contacts::OwnerUuid)
+                            .to(Users::Table, Users::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-contacts-contact")
+                            .from(Contacts::Table, Contacts::ContactUuid)
+                            .to(Users::Table, Users::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Contacts::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            "DO $$ \
+             DECLARE \
+                 loser RECORD; \
+                 candidate TEXT; \
+                 attempt INT; \
+             BEGIN \
+                 FOR loser IN \
+                     SELECT uuid, username FROM ( \
+                         SELECT uuid, username, row_number() OVER ( \
+                             PARTITION BY lower(username) ORDER BY created_at, uuid \
+                         ) AS position \
+                         FROM users \
+                     ) AS ranked \
+                     WHERE position > 1 \
+                 LOOP \
+                     attempt := 0; \
+                     LOOP \
+                         candidate := left(loser.username, 11) || '-' \
+                             || substr(md5(loser.uuid::text || attempt::text), 1, 8); \
+                         EXIT WHEN NOT EXISTS ( \
+                             SELECT 1 FROM users WHERE lower(username) = lower(candidate) \
+                         ); \
+                         attempt := attempt + 1; \
+                     END LOOP; \
+                     INSERT INTO username_redirects (username, user_uuid) \
+                     VALUES (loser.username, loser.uuid) \
+                     ON CONFLICT (username) DO UPDATE \
+                     SET user_uuid = EXCLUDED.user_uuid, created_at = EXCLUDED.created_at; \
+                     UPDATE users SET username = candidate WHERE uuid = loser.uuid; \
+                 END LOOP; \
+             END $$",
+        )
+        .await?;
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX IF NOT EXISTS \"idx-users-username-lower\" \
+             ON users (lower(username))",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared("DROP INDEX IF EXISTS \"idx-users-username-lower\"")
+            .await?;
+
+        Ok(())
+    }
+}
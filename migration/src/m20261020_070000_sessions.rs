@@ -0,0 +1,72 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20251011_133950_users::Users;
+
+#[derive(DeriveIden)]
+pub enum Sessions {
+    Table,
+    Uuid,
+    UserUuid,
+    RefreshTokenHash,
+    CreatedAt,
+    RefreshedAt,
+    ExpiresAt,
+    RevokedAt,
+    PreviousRefreshTokenHash,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Sessions::Table)
+                    .if_not_exists()
+                    .col(uuid(Sessions::Uuid).primary_key())
+                    .col(uuid(Sessions::UserUuid).not_null())
+                    .col(text(Sessions::RefreshTokenHash).not_null().unique_key())
+                    .col(
+                        timestamp(Sessions::CreatedAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp(Sessions::RefreshedAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(timestamp(Sessions::ExpiresAt).not_null())
+                    .col(timestamp_null(Sessions::RevokedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-sessions-user")
+                            .from(Sessions::Table, Sessions::UserUuid)
+                            .to(Users::Table, Users::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-sessions-user")
+                    .table(Sessions::Table)
+                    .col(Sessions::UserUuid)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Sessions::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,85 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20251011_133950_users::Users;
+
+#[derive(DeriveIden)]
+pub enum AccessTokens {
+    Table,
+    Uuid,
+    UserUuid,
+    Name,
+    TokenHash,
+    ReadMessages,
+    SendMessages,
+    ManageGroups,
+    CreatedAt,
+    ExpiresAt,
+    LastUsedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AccessTokens::Table)
+                    .if_not_exists()
+                    .col(uuid(AccessTokens::Uuid).primary_key())
+                    .col(uuid(AccessTokens::UserUuid).not_null())
+                    .col(text(AccessTokens::Name).not_null())
+                    .col(text(AccessTokens::TokenHash).not_null().unique_key())
+                    .col(
+                        boolean(AccessTokens::ReadMessages)
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        boolean(AccessTokens::SendMessages)
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        boolean(AccessTokens::ManageGroups)
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        timestamp(AccessTokens::CreatedAt)
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(timestamp_null(AccessTokens::ExpiresAt))
+                    .col(timestamp_null(AccessTokens::LastUsedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-access-tokens-user")
+                            .from(AccessTokens::Table, AccessTokens::UserUuid)
+                            .to(Users::Table, Users::Uuid)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-access-tokens-user")
+                    .table(AccessTokens::Table)
+                    .col(AccessTokens::UserUuid)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AccessTokens::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20261019_233000_chat_folders::ChatFolders;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatFolders::Table)
+                    .add_column(boolean(ChatFolders::IncludeAll).not_null().default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE chat_folders SET include_all = true
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM chat_folder_chats
+                    WHERE chat_folder_chats.folder_uuid = chat_folders.uuid
+                )",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatFolders::Table)
+                    .drop_column(ChatFolders::IncludeAll)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::Context;
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{Duration, Utc};
+use sea_orm::{
+    prelude::Uuid, ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+
+use crate::{
+    db,
+    jwt::{check_user, hash_token, random_token},
+    schema::*,
+    AppError, AuthUser,
+};
+use utils::{
+    data::{AccessTokenInfo, TokenScope},
+    errors::{AuthReason, Resource},
+    requests::{
+        CreateAccessTokenRequest, CreateAccessTokenResponse, ListAccessTokensResponse,
+        RevokeAccessTokenRequest, RevokeAccessTokenResponse,
+    },
+    validation::{validate_expiry, validate_token_name, Field, Reason, ValidationError},
+};
+
+const TOKEN_PREFIX: &str = "cmt_";
+const LAST_USED_RESOLUTION_SECONDS: i64 = 60;
+
+fn token_scopes(token: &access_tokens::Model) -> Vec<TokenScope> {
+    TokenScope::ALL
+        .into_iter()
+        .filter(|scope| has_scope(token, *scope))
+        .collect()
+}
+
+fn has_scope(token: &access_tokens::Model, scope: TokenScope) -> bool {
+    match scope {
+        TokenScope::ReadMessages => token.read_messages,
+        TokenScope::SendMessages => token.send_messages,
+        TokenScope::ManageGroups => token.manage_groups,
+    }
+}
+
+fn access_token_info(token: access_tokens::Model) -> AccessTokenInfo {
+    AccessTokenInfo {
+        uuid: token.uuid,
+        scopes: token_scopes(&token),
+        name: token.name,
+        created_at: token.created_at,
+        expires_at: token.expires_at,
+        last_used_at: token.last_used_at,
+    }
+}
+
+pub fn is_access_token(token: &str) -> bool {
+    token.starts_with(TOKEN_PREFIX)
+}
+
+pub async fn verify_access_token(
+    token: &str,
+    scope: Option<TokenScope>,
+) -> Result<users::Model, AppError> {
+    authenticate(token, |access_token| {
+        scope.is_some_and(|scope| has_scope(access_token, scope))
+    })
+    .await
+}
+
+pub async fn verify_access_token_unscoped(token: &str) -> Result<users::Model, AppError> {
+    authenticate(token, |_| true).await
+}
+
+async fn authenticate(
+    token: &str,
+    scope_allowed: impl FnOnce(&access_tokens::Model) -> bool,
+) -> Result<users::Model, AppError> {
+    let db = db().await;
+    let now = Utc::now().naive_utc();
+
+    let access_token = AccessTokens::find()
+        .filter(access_tokens::Column::TokenHash.eq(hash_token(token)))
+        .one(db)
+        .await
+        .context("Failed to query access token from database")?
+        .ok_or(AppError::Auth(AuthReason::InvalidToken))?;
+
+    if access_token
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= now)
+    {
+        return Err(AppError::Auth(AuthReason::ExpiredToken));
+    }
+    if !scope_allowed(&access_token) {
+        return Err(AppError::Auth(AuthReason::InsufficientScope));
+    }
+
+    let user = Users::find_by_id(access_token.user_uuid)
+        .one(db)
+        .await
+        .context("Failed to query user from database")?
+        .ok_or(AppError::Auth(AuthReason::UserNotFound))?;
+    check_user(&user).map_err(AppError::Auth)?;
+
+    let stale = access_token.last_used_at.is_none_or(|last_used_at| {
+        now - last_used_at >= Duration::seconds(LAST_USED_RESOLUTION_SECONDS)
+    });
+    if stale {
+        let mut access_token_active: access_tokens::ActiveModel = access_token.into();
+        access_token_active.last_used_at = Set(Some(now));
+        access_token_active
+            .update(db)
+            .await
+            .context("Failed to update access token in database")?;
+    }
+
+    Ok(user)
+}
+
+pub async fn list_access_tokens(AuthUser(user): AuthUser) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let tokens: Vec<AccessTokenInfo> = AccessTokens::find()
+        .filter(access_tokens::Column::UserUuid.eq(user.uuid))
+        .order_by_desc(access_tokens::Column::CreatedAt)
+        .all(db)
+        .await
+        .context("Failed to query access tokens from database")?
+        .into_iter()
+        .map(access_token_info)
+        .collect();
+
+    let response = ListAccessTokensResponse(tokens);
+    Ok(Json(response).into_response())
+}
+
+pub async fn create_access_token(
+    AuthUser(user): AuthUser,
+    Json(body): Json<CreateAccessTokenRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let name = validate_token_name(&body.name).map_err(AppError::validation)?;
+    if body.scopes.is_empty() {
+        return Err(AppError::validation(ValidationError::new(
+            Field::TokenScopes,
+            Reason::Empty,
+        )));
+    }
+    validate_expiry(Field::TokenExpiry, body.expires_at, Utc::now().naive_utc())
+        .map_err(AppError::validation)?;
+
+    let token = format!("{}{}", TOKEN_PREFIX, random_token());
+
+    let access_token_model = access_tokens::ActiveModel {
+        uuid: Set(Uuid::new_v4()),
+        user_uuid: Set(user.uuid),
+        name: Set(name),
+        token_hash: Set(hash_token(&token)),
+        read_messages: Set(body.scopes.contains(&TokenScope::ReadMessages)),
+        send_messages: Set(body.scopes.contains(&TokenScope::SendMessages)),
+        manage_groups: Set(body.scopes.contains(&TokenScope::ManageGroups)),
+        expires_at: Set(body.expires_at),
+        ..Default::default()
+    };
+    let access_token = access_token_model
+        .insert(db)
+        .await
+        .context("Failed to insert access token into database")?;
+
+    let response = CreateAccessTokenResponse {
+        token,
+        info: access_token_info(access_token),
+    };
+    Ok(Json(response).into_response())
+}
+
+pub async fn revoke_access_token(
+    AuthUser(user): AuthUser,
+    Json(body): Json<RevokeAccessTokenRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let result = AccessTokens::delete_many()
+        .filter(access_tokens::Column::Uuid.eq(body.0))
+        .filter(access_tokens::Column::UserUuid.eq(user.uuid))
+        .exec(db)
+        .await
+        .context("Failed to delete access token from database")?;
+
+    if result.rows_affected == 0 {
+        return Err(AppError::not_found(Resource::AccessToken));
+    }
+
+    let response = RevokeAccessTokenResponse {};
+    Ok(Json(response).into_response())
+}
@@ -0,0 +1,337 @@
+use anyhow::{anyhow, Context};
+use axum::{
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use sea_orm::{
+    sea_query::Expr, ActiveEnum, ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition,
+    EntityTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+use serde_json::{json, Value};
+use std::io::{Cursor, Write};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    chats::transfer_ownership,
+    conn::{disconnect, publish},
+    db,
+    folders::user_folders,
+    jwt::revoke_sessions,
+    schema::*,
+    users::user_info,
+    AppError, AuthUser,
+};
+use utils::{
+    requests::{DeleteAccountRequest, DeleteAccountResponse},
+    updates::Update,
+    validation::username_key,
+};
+
+const DELETED_NICKNAME: &str = "Deleted account";
+
+fn export_zip(files: Vec<(&str, Value)>) -> anyhow::Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (name, value) in files {
+        zip.start_file(name, options)
+            .context("Failed to start export file")?;
+        let content =
+            serde_json::to_vec_pretty(&value).context("Failed to serialize export file")?;
+        zip.write_all(&content)
+            .context("Failed to write export file")?;
+    }
+
+    let cursor = zip.finish().context("Failed to finish export archive")?;
+    Ok(cursor.into_inner())
+}
+
+pub async fn export_data(AuthUser(user): AuthUser) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let profile = json!({
+        "uuid": user.uuid,
+        "email": user.email,
+        "username": user.username,
+        "bio": user.bio,
+        "nickname": user.nickname,
+        "created_at": user.created_at,
+        "last_seen_at": user.last_seen_at,
+    });
+
+    let membership_models: Vec<(chat_members::Model, Option<chats::Model>)> = ChatMembers::find()
+        .filter(chat_members::Column::UserUuid.eq(user.uuid))
+        .find_also_related(Chats)
+        .all(db)
+        .await
+        .context("Failed to query chat memberships from database")?;
+    let memberships: Vec<Value> = membership_models
+        .into_iter()
+        .map(|(membership, chat)| {
+            json!({
+                "chat_uuid": membership.chat_uuid,
+                "chat_name": chat.as_ref().map(|c| c.name.clone()),
+                "chat_kind": chat.map(|c| c.kind.to_value()),
+                "role": membership.role.to_value(),
+                "joined_at": membership.joined_at,
+                "muted_until": membership.muted_until,
+                "pinned": membership.pinned,
+                "archived": membership.archived,
+            })
+        })
+        .collect();
+
+    let message_models: Vec<messages::Model> = Messages::find()
+        .filter(messages::Column::SenderUuid.eq(user.uuid))
+        .order_by_asc(messages::Column::CreatedAt)
+        .all(db)
+        .await
+        .context("Failed to query messages from database")?;
+    let messages: Vec<Value> = message_models
+        .into_iter()
+        .map(|message| {
+            json!({
+                "uuid": message.uuid,
+                "chat_uuid": message.chat_uuid,
+                "content": message.content,
+                "created_at": message.created_at,
+                "edited_at": message.edited_at,
+                "deleted": message.deleted,
+                "reply": message.reply,
+            })
+        })
+        .collect();
+
+    let block_models: Vec<user_blocks::Model> = UserBlocks::find()
+        .filter(user_blocks::Column::BlockerUuid.eq(user.uuid))
+        .all(db)
+        .await
+        .context("Failed to query user blocks from database")?;
+    let blocks: Vec<Value> = block_models
+        .into_iter()
+        .map(|block| {
+            json!({
+                "blocked_uuid": block.blocked_uuid,
+                "created_at": block.created_at,
+            })
+        })
+        .collect();
+
+    let contact_models: Vec<contacts::Model> = Contacts::find()
+        .filter(contacts::Column::OwnerUuid.eq(user.uuid))
+        .all(db)
+        .await
+        .context("Failed to query contacts from database")?;
+    let contacts: Vec<Value> = contact_models
+        .into_iter()
+        .map(|contact| {
+            json!({
+                "contact_uuid": contact.contact_uuid,
+                "nickname": contact.nickname,
+                "created_at": contact.created_at,
+            })
+        })
+        .collect();
+
+    let report_models: Vec<reports::Model> = Reports::find()
+        .filter(reports::Column::ReporterUuid.eq(user.uuid))
+        .order_by_asc(reports::Column::CreatedAt)
+        .all(db)
+        .await
+        .context("Failed to query reports from database")?;
+    let reports: Vec<Value> = report_models
+        .into_iter()
+        .map(|report| {
+            json!({
+                "message_uuid": report.message_uuid,
+                "reason": report.reason,
+                "created_at": report.created_at,
+                "resolved_at": report.resolved_at,
+            })
+        })
+        .collect();
+
+    let folders = user_folders(user.uuid).await?;
+
+    let archive = export_zip(vec![
+        ("profile.json", profile),
+        ("memberships.json", Value::from(memberships)),
+        ("messages.json", Value::from(messages)),
+        ("blocks.json", Value::from(blocks)),
+        ("contacts.json", Value::from(contacts)),
+        ("reports.json", Value::from(reports)),
+        (
+            "folders.json",
+            serde_json::to_value(folders).context("Failed to serialize chat folders")?,
+        ),
+    ])?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, "application/zip"),
+            (
+                CONTENT_DISPOSITION,
+                "attachment; filename=\"commeator-export.zip\"",
+            ),
+        ],
+        archive,
+    )
+        .into_response())
+}
+
+pub async fn delete_account(
+    AuthUser(user): AuthUser,
+    Json(body): Json<DeleteAccountRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    if username_key(&body.confirm_username) != username_key(&user.username) {
+        return Err(anyhow!("Confirmation does not match username").into());
+    }
+
+    let txn = db
+        .begin()
+        .await
+        .context("Failed to start database transaction")?;
+
+    let memberships: Vec<(chat_members::Model, Option<chats::Model>)> = ChatMembers::find()
+        .filter(chat_members::Column::UserUuid.eq(user.uuid))
+        .find_also_related(Chats)
+        .all(&txn)
+        .await
+        .context("Failed to query chat memberships from database")?;
+
+    let mut remaining_chats = Vec::new();
+    for (membership, chat) in memberships {
+        let Some(chat) = chat else {
+            continue;
+        };
+
+        if membership.role != sea_orm_active_enums::ChatRole::Owner
+            || chat.kind == sea_orm_active_enums::ChatKind::Private
+        {
+            remaining_chats.push(chat.uuid);
+            continue;
+        }
+
+        if transfer_ownership(&txn, chat.uuid, user.uuid).await? {
+            remaining_chats.push(chat.uuid);
+        } else {
+            Chats::delete_by_id(chat.uuid)
+                .exec(&txn)
+                .await
+                .context("Failed to dissolve chat in database")?;
+        }
+    }
+
+    Chats::update_many()
+        .col_expr(chats::Column::Name, Expr::value(String::new()))
+        .filter(
+            Condition::any()
+                .add(chats::Column::PrivateFirstUuid.eq(user.uuid))
+                .add(chats::Column::PrivateSecondUuid.eq(user.uuid)),
+        )
+        .exec(&txn)
+        .await
+        .context("Failed to clear private chat names in database")?;
+
+    ChatMembers::delete_many()
+        .filter(chat_members::Column::UserUuid.eq(user.uuid))
+        .exec(&txn)
+        .await
+        .context("Failed to delete chat memberships from database")?;
+
+    ChatFolders::delete_many()
+        .filter(chat_folders::Column::UserUuid.eq(user.uuid))
+        .exec(&txn)
+        .await
+        .context("Failed to delete chat folders from database")?;
+
+    UserBlocks::delete_many()
+        .filter(
+            Condition::any()
+                .add(user_blocks::Column::BlockerUuid.eq(user.uuid))
+                .add(user_blocks::Column::BlockedUuid.eq(user.uuid)),
+        )
+        .exec(&txn)
+        .await
+        .context("Failed to delete user blocks from database")?;
+
+    Contacts::delete_many()
+        .filter(
+            Condition::any()
+                .add(contacts::Column::OwnerUuid.eq(user.uuid))
+                .add(contacts::Column::ContactUuid.eq(user.uuid)),
+        )
+        .exec(&txn)
+        .await
+        .context("Failed to delete contacts from database")?;
+
+    Avatars::delete_many()
+        .filter(avatars::Column::UserUuid.eq(user.uuid))
+        .exec(&txn)
+        .await
+        .context("Failed to delete avatars from database")?;
+
+    UsernameRedirects::delete_many()
+        .filter(username_redirects::Column::UserUuid.eq(user.uuid))
+        .exec(&txn)
+        .await
+        .context("Failed to delete username redirects from database")?;
+
+    revoke_sessions(&txn, user.uuid).await?;
+
+    AccessTokens::delete_many()
+        .filter(access_tokens::Column::UserUuid.eq(user.uuid))
+        .exec(&txn)
+        .await
+        .context("Failed to delete access tokens from database")?;
+
+    let placeholder = format!("deleted-{}", user.uuid.simple());
+    let email = format!("{}@deleted.invalid", placeholder);
+    let email_hash = format!("{:x}", md5::compute(&email));
+
+    let user_uuid = user.uuid;
+    let mut user_active: users::ActiveModel = user.into();
+    user_active.email = Set(email);
+    user_active.email_hash = Set(email_hash);
+    user_active.username = Set(placeholder);
+    user_active.nickname = Set(DELETED_NICKNAME.to_string());
+    user_active.is_moderator = Set(false);
+    user_active.last_seen_at = Set(None);
+    user_active.avatar_updated_at = Set(None);
+    user_active.bio = Set(String::new());
+    user_active.status_emoji = Set(None);
+    user_active.status_text = Set(None);
+    user_active.status_expires_at = Set(None);
+    user_active.deleted_at = Set(Some(Utc::now().naive_utc()));
+    let user = user_active
+        .update(&txn)
+        .await
+        .context("Failed to anonymize user in database")?;
+
+    txn.commit()
+        .await
+        .context("Failed to commit database transaction")?;
+
+    let user = user_info(user);
+    for chat_uuid in remaining_chats {
+        if let Err(e) = publish(
+            &format!("chat_{}", chat_uuid),
+            Update::UserUpdated(user.clone()),
+        )
+        .await
+        {
+            tracing::error!("Failed to publish user update: {:?}", e);
+        }
+    }
+
+    if let Err(e) = disconnect(user_uuid).await {
+        tracing::error!("Failed to disconnect deleted user: {:?}", e);
+    }
+
+    let response = DeleteAccountResponse {};
+    Ok(Json(response).into_response())
+}
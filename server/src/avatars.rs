@@ -0,0 +1,247 @@
+use anyhow::{anyhow, Context};
+use axum::{
+    body::Bytes,
+    extract::{Path, Query},
+    http::header::{CACHE_CONTROL, CONTENT_TYPE},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use image::{
+    imageops::FilterType, DynamicImage, ImageFormat, ImageReader, Limits, Rgba, RgbaImage,
+};
+use sea_orm::{
+    prelude::Uuid, ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter,
+    TransactionTrait,
+};
+use serde::Deserialize;
+use std::io::Cursor;
+
+use crate::{
+    db,
+    schema::*,
+    users::{publish_user_updated, user_info},
+    AppError, AuthUser,
+};
+use utils::requests::{RemoveAvatarResponse, UploadAvatarResponse};
+
+pub const MAX_UPLOAD_BYTES: usize = 5 * 1024 * 1024;
+const MAX_SOURCE_DIMENSION: u32 = 4096;
+const AVATAR_SIZES: [u32; 3] = [64, 128, 256];
+const DEFAULT_AVATAR_SIZE: u32 = 128;
+const IDENTICON_GRID: u32 = 5;
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+const REVALIDATE_CACHE_CONTROL: &str = "public, max-age=60, must-revalidate";
+
+#[derive(Debug, Deserialize)]
+pub struct AvatarQuery {
+    size: Option<u32>,
+    v: Option<i64>,
+}
+
+fn avatar_size(requested: Option<u32>) -> u32 {
+    let requested = requested.unwrap_or(DEFAULT_AVATAR_SIZE);
+    AVATAR_SIZES
+        .into_iter()
+        .find(|size| *size >= requested)
+        .unwrap_or(AVATAR_SIZES[AVATAR_SIZES.len() - 1])
+}
+
+fn encode_png(image: &DynamicImage) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Cursor::new(Vec::new());
+    image
+        .write_to(&mut bytes, ImageFormat::Png)
+        .context("Failed to encode avatar")?;
+    Ok(bytes.into_inner())
+}
+
+fn identicon(user_uuid: Uuid, size: u32) -> anyhow::Result<Vec<u8>> {
+    let hash = md5::compute(user_uuid.as_bytes()).0;
+    let color = Rgba([
+        64 + hash[13] % 128,
+        64 + hash[14] % 128,
+        64 + hash[15] % 128,
+        255,
+    ]);
+    let mut image = RgbaImage::from_pixel(size, size, Rgba([240, 240, 240, 255]));
+
+    let cell = size / (IDENTICON_GRID + 1);
+    let margin = (size - cell * IDENTICON_GRID) / 2;
+    for row in 0..IDENTICON_GRID {
+        for col in 0..IDENTICON_GRID.div_ceil(2) {
+            if hash[(row * 3 + col) as usize] & 1 == 0 {
+                continue;
+            }
+
+            for grid_col in [col, IDENTICON_GRID - 1 - col] {
+                for y in 0..cell {
+                    for x in 0..cell {
+                        image.put_pixel(
+                            margin + grid_col * cell + x,
+                            margin + row * cell + y,
+                            color,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    encode_png(&DynamicImage::ImageRgba8(image))
+}
+
+fn process_upload(bytes: Bytes) -> anyhow::Result<Vec<(u32, Vec<u8>)>> {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+
+    let mut reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .context("Failed to read avatar image")?;
+    reader.limits(limits);
+    let source = reader
+        .decode()
+        .context("Unsupported or invalid avatar image")?;
+
+    let side = source.width().min(source.height());
+    let cropped = source.crop_imm(
+        (source.width() - side) / 2,
+        (source.height() - side) / 2,
+        side,
+        side,
+    );
+
+    AVATAR_SIZES
+        .into_iter()
+        .map(|size| {
+            let resized = cropped.resize_exact(size, size, FilterType::Lanczos3);
+            Ok((size, encode_png(&resized)?))
+        })
+        .collect()
+}
+
+pub async fn get_avatar(
+    Path(user_uuid): Path<Uuid>,
+    Query(query): Query<AvatarQuery>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+    let size = avatar_size(query.size);
+
+    let avatar: Option<avatars::Model> = Avatars::find_by_id((user_uuid, size as i32))
+        .one(db)
+        .await
+        .context("Failed to query avatar from database")?;
+
+    let (data, cache_control) = match avatar {
+        Some(avatar) => {
+            let avatar_version = Users::find_by_id(user_uuid)
+                .one(db)
+                .await
+                .context("Failed to query user from database")?
+                .and_then(|user| user.avatar_updated_at)
+                .map(|updated_at| updated_at.and_utc().timestamp_millis());
+            let cache_control = if query.v.is_some() && query.v == avatar_version {
+                IMMUTABLE_CACHE_CONTROL
+            } else {
+                REVALIDATE_CACHE_CONTROL
+            };
+            (avatar.data, cache_control)
+        }
+        None => (identicon(user_uuid, size)?, REVALIDATE_CACHE_CONTROL),
+    };
+
+    Ok((
+        [(CONTENT_TYPE, "image/png"), (CACHE_CONTROL, cache_control)],
+        data,
+    )
+        .into_response())
+}
+
+pub async fn upload_avatar(AuthUser(user): AuthUser, body: Bytes) -> Result<Response, AppError> {
+    let db = db().await;
+
+    if body.is_empty() {
+        return Err(anyhow!("Avatar image is empty").into());
+    }
+
+    let resized = tokio::task::spawn_blocking(move || process_upload(body))
+        .await
+        .context("Failed to process avatar image")??;
+
+    let txn = db
+        .begin()
+        .await
+        .context("Failed to start database transaction")?;
+
+    Avatars::delete_many()
+        .filter(avatars::Column::UserUuid.eq(user.uuid))
+        .exec(&txn)
+        .await
+        .context("Failed to delete old avatars from database")?;
+
+    let user_uuid = user.uuid;
+    let new_avatars = resized
+        .into_iter()
+        .map(|(size, data)| avatars::ActiveModel {
+            user_uuid: Set(user_uuid),
+            size: Set(size as i32),
+            data: Set(data),
+        });
+    Avatars::insert_many(new_avatars)
+        .exec(&txn)
+        .await
+        .context("Failed to insert avatars into database")?;
+
+    let mut user_active: users::ActiveModel = user.into();
+    user_active.avatar_updated_at = Set(Some(Utc::now().naive_utc()));
+    let user = user_active
+        .update(&txn)
+        .await
+        .context("Failed to update user in database")?;
+
+    txn.commit()
+        .await
+        .context("Failed to commit database transaction")?;
+
+    let user = user_info(user);
+    if let Err(e) = publish_user_updated(&user).await {
+        tracing::error!("Failed to publish user update: {:?}", e);
+    }
+
+    let response = UploadAvatarResponse(user);
+    Ok(Json(response).into_response())
+}
+
+pub async fn remove_avatar(AuthUser(user): AuthUser) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let txn = db
+        .begin()
+        .await
+        .context("Failed to start database transaction")?;
+
+    Avatars::delete_many()
+        .filter(avatars::Column::UserUuid.eq(user.uuid))
+        .exec(&txn)
+        .await
+        .context("Failed to delete avatars from database")?;
+
+    let mut user_active: users::ActiveModel = user.into();
+    user_active.avatar_updated_at = Set(None);
+    let user = user_active
+        .update(&txn)
+        .await
+        .context("Failed to update user in database")?;
+
+    txn.commit()
+        .await
+        .context("Failed to commit database transaction")?;
+
+    let user = user_info(user);
+    if let Err(e) = publish_user_updated(&user).await {
+        tracing::error!("Failed to publish user update: {:?}", e);
+    }
+
+    let response = RemoveAvatarResponse(user);
+    Ok(Json(response).into_response())
+}
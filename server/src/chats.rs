@@ -1,72 +1,265 @@
 use anyhow::{anyhow, Context};
+use std::{cmp::Reverse, collections::HashMap};
+
 use axum::{
-    http::HeaderMap,
     response::{IntoResponse, Response},
     Json,
 };
 use sea_orm::{
-    sea_query::Query, ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, JoinType,
-    QueryFilter, QuerySelect, RelationTrait,
+    prelude::Uuid, ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, SqlErr, TransactionTrait,
 };
 
-use crate::{db, schema::*, verify_jwt, AppError};
+use crate::{
+    conn::publish,
+    db,
+    privacy::audience_allows,
+    schema::*,
+    users::{is_blocked, user_info},
+    AppError, AuthUser,
+};
 use utils::{
-    data::ChatInfo,
+    data::{self, ChatInfo, ChatPreferences, MessagePreview},
+    errors::ForbiddenReason,
     requests::{
         GetChatRequest, GetChatResponse, ListChatsResponse, NewChatResponse, NewGroupRequest,
-        VerifyPrivateChatRequest,
+        SetChatPreferencesRequest, SetChatPreferencesResponse, SetSlowModeRequest,
+        SetSlowModeResponse, VerifyPrivateChatRequest,
     },
+    updates::{SlowModeChangedPayload, Update, UpdateChatPreferencesPayload},
+    validation::{validate_group_title, validate_slow_mode},
 };
 
-pub async fn list_chats(headers: HeaderMap) -> Result<Response, AppError> {
-    let user = verify_jwt(&headers).await?;
-    let db = db().await;
+impl From<sea_orm_active_enums::ChatKind> for data::ChatKind {
+    fn from(kind: sea_orm_active_enums::ChatKind) -> Self {
+        match kind {
+            sea_orm_active_enums::ChatKind::Private => data::ChatKind::Private,
+            sea_orm_active_enums::ChatKind::Group => data::ChatKind::Group,
+            sea_orm_active_enums::ChatKind::Broadcast => data::ChatKind::Broadcast,
+        }
+    }
+}
 
-    let chat_ids: Vec<chat_members::Model> = ChatMembers::find()
-        .filter(chat_members::Column::UserUuid.eq(user.uuid))
-        .all(db)
+impl From<data::ChatKind> for sea_orm_active_enums::ChatKind {
+    fn from(kind: data::ChatKind) -> Self {
+        match kind {
+            data::ChatKind::Private => sea_orm_active_enums::ChatKind::Private,
+            data::ChatKind::Group => sea_orm_active_enums::ChatKind::Group,
+            data::ChatKind::Broadcast => sea_orm_active_enums::ChatKind::Broadcast,
+        }
+    }
+}
+
+impl From<sea_orm_active_enums::ChatRole> for data::ChatRole {
+    fn from(role: sea_orm_active_enums::ChatRole) -> Self {
+        match role {
+            sea_orm_active_enums::ChatRole::Owner => data::ChatRole::Owner,
+            sea_orm_active_enums::ChatRole::Admin => data::ChatRole::Admin,
+            sea_orm_active_enums::ChatRole::Member => data::ChatRole::Member,
+        }
+    }
+}
+
+fn chat_preferences(membership: &chat_members::Model) -> ChatPreferences {
+    ChatPreferences {
+        muted_until: membership.muted_until,
+        pinned: membership.pinned,
+        archived: membership.archived,
+    }
+}
+
+fn chat_info(
+    chat: chats::Model,
+    membership: &chat_members::Model,
+    member_count: u64,
+    counterpart: Option<users::Model>,
+    last_message: Option<MessagePreview>,
+) -> ChatInfo {
+    let counterpart = counterpart.map(user_info);
+    let last_activity = last_message
+        .as_ref()
+        .map_or(chat.created_at, |message| message.created_at);
+    let name = match &counterpart {
+        Some(counterpart) => counterpart.nickname.clone(),
+        None => chat.name,
+    };
+
+    ChatInfo {
+        uuid: chat.uuid,
+        name,
+        kind: chat.kind.into(),
+        role: membership.role.clone().into(),
+        member_count,
+        preferences: chat_preferences(membership),
+        counterpart,
+        last_message,
+        last_activity,
+        slow_mode: chat.slow_mode_seconds.map(|seconds| seconds as u32),
+    }
+}
+
+async fn last_messages(chat_uuids: Vec<Uuid>) -> Result<HashMap<Uuid, MessagePreview>, AppError> {
+    let messages: Vec<(messages::Model, Option<users::Model>)> = Messages::find()
+        .distinct_on([messages::Column::ChatUuid])
+        .filter(messages::Column::ChatUuid.is_in(chat_uuids))
+        .filter(messages::Column::Deleted.eq(false))
+        .order_by_asc(messages::Column::ChatUuid)
+        .order_by_desc(messages::Column::CreatedAt)
+        .find_also_related(Users)
+        .all(db().await)
         .await
-        .context("Failed to query chat memberships from database")?;
+        .context("Failed to query last messages from database")?;
 
-    let chat_models: Vec<chats::Model> = Chats::find()
-        .filter(chats::Column::Uuid.is_in(chat_ids.iter().map(|cm| cm.chat_uuid)))
+    Ok(messages
+        .into_iter()
+        .filter_map(|(message, sender)| {
+            sender.map(|sender| {
+                let preview = MessagePreview {
+                    uuid: message.uuid,
+                    sender_uuid: sender.uuid,
+                    sender_nickname: sender.nickname,
+                    content: MessagePreview::truncate(&message.content),
+                    created_at: message.created_at,
+                };
+                (message.chat_uuid, preview)
+            })
+        })
+        .collect())
+}
+
+async fn member_counts(chat_uuids: Vec<Uuid>) -> Result<HashMap<Uuid, u64>, AppError> {
+    let counts: Vec<(Uuid, i64)> = ChatMembers::find()
+        .select_only()
+        .column(chat_members::Column::ChatUuid)
+        .column_as(chat_members::Column::UserUuid.count(), "count")
+        .filter(chat_members::Column::ChatUuid.is_in(chat_uuids))
+        .group_by(chat_members::Column::ChatUuid)
+        .into_tuple()
+        .all(db().await)
+        .await
+        .context("Failed to query chat member counts from database")?;
+
+    Ok(counts
+        .into_iter()
+        .map(|(chat_uuid, count)| (chat_uuid, count as u64))
+        .collect())
+}
+
+async fn counterparts(
+    viewer_uuid: Uuid,
+    chats: Vec<&chats::Model>,
+) -> Result<HashMap<Uuid, users::Model>, AppError> {
+    let pairs: Vec<(Uuid, Uuid)> = chats
+        .into_iter()
+        .filter_map(|chat| {
+            [chat.private_first_uuid, chat.private_second_uuid]
+                .into_iter()
+                .flatten()
+                .find(|uuid| *uuid != viewer_uuid)
+                .map(|counterpart_uuid| (chat.uuid, counterpart_uuid))
+        })
+        .collect();
+
+    let users: HashMap<Uuid, users::Model> = Users::find()
+        .filter(users::Column::Uuid.is_in(pairs.iter().map(|(_, uuid)| *uuid)))
+        .all(db().await)
+        .await
+        .context("Failed to query private chat counterparts from database")?
+        .into_iter()
+        .map(|user| (user.uuid, user))
+        .collect();
+
+    Ok(pairs
+        .into_iter()
+        .filter_map(|(chat_uuid, counterpart_uuid)| {
+            users
+                .get(&counterpart_uuid)
+                .cloned()
+                .map(|user| (chat_uuid, user))
+        })
+        .collect())
+}
+
+pub async fn transfer_ownership<C: ConnectionTrait>(
+    db: &C,
+    chat_uuid: Uuid,
+    owner_uuid: Uuid,
+) -> Result<bool, AppError> {
+    let successor: Option<chat_members::Model> = ChatMembers::find()
+        .filter(chat_members::Column::ChatUuid.eq(chat_uuid))
+        .filter(chat_members::Column::UserUuid.ne(owner_uuid))
+        .order_by_asc(chat_members::Column::Role)
+        .order_by_asc(chat_members::Column::JoinedAt)
+        .one(db)
+        .await
+        .context("Failed to query chat members from database")?;
+
+    let Some(successor) = successor else {
+        return Ok(false);
+    };
+
+    let mut successor_active: chat_members::ActiveModel = successor.into();
+    successor_active.role = Set(sea_orm_active_enums::ChatRole::Owner);
+    successor_active
+        .update(db)
+        .await
+        .context("Failed to transfer chat ownership in database")?;
+
+    Ok(true)
+}
+
+pub async fn list_chats(AuthUser(user): AuthUser) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let memberships: Vec<(chat_members::Model, Option<chats::Model>)> = ChatMembers::find()
+        .filter(chat_members::Column::UserUuid.eq(user.uuid))
+        .find_also_related(Chats)
         .all(db)
         .await
         .context("Failed to query chats from database")?;
 
-    let chats = chat_models
+    let counts = member_counts(memberships.iter().map(|(cm, _)| cm.chat_uuid).collect()).await?;
+    let private_chats = memberships
+        .iter()
+        .filter_map(|(_, chat)| chat.as_ref())
+        .filter(|chat| chat.kind == sea_orm_active_enums::ChatKind::Private)
+        .collect();
+    let mut counterparts = counterparts(user.uuid, private_chats).await?;
+    let mut last_messages =
+        last_messages(memberships.iter().map(|(cm, _)| cm.chat_uuid).collect()).await?;
+
+    let mut chats: Vec<ChatInfo> = memberships
         .into_iter()
-        .map(|chat| ChatInfo {
-            uuid: chat.uuid,
-            name: chat.name,
-            is_group: chat.is_group,
+        .filter_map(|(membership, chat)| {
+            chat.map(|chat| {
+                let member_count = counts.get(&chat.uuid).copied().unwrap_or_default();
+                let counterpart = counterparts.remove(&chat.uuid);
+                let last_message = last_messages.remove(&chat.uuid);
+                chat_info(chat, &membership, member_count, counterpart, last_message)
+            })
         })
         .collect();
+    chats.sort_by_key(|chat| Reverse(chat.last_activity));
 
     let response = ListChatsResponse(chats);
     Ok(Json(response).into_response())
 }
 
 pub async fn get_chat(
-    headers: HeaderMap,
+    AuthUser(user): AuthUser,
     Json(body): Json<GetChatRequest>,
 ) -> Result<Response, AppError> {
-    let user = verify_jwt(&headers).await?;
     let db = db().await;
 
     let chat_uuid = body.0;
 
-    let is_member = ChatMembers::find()
+    let membership: chat_members::Model = ChatMembers::find()
         .filter(chat_members::Column::ChatUuid.eq(chat_uuid))
         .filter(chat_members::Column::UserUuid.eq(user.uuid))
         .one(db)
         .await
         .context("Failed to query chat membership from database")?
-        .is_some();
-
-    if !is_member {
-        return Err(anyhow!("User is not a member of this chat").into());
-    }
+        .ok_or_else(|| anyhow!("User is not a member of this chat"))?;
 
     let chat_model: chats::Model = Chats::find_by_id(chat_uuid)
         .one(db)
@@ -74,25 +267,52 @@ pub async fn get_chat(
         .context("Failed to query chat from database")?
         .ok_or_else(|| anyhow!("Chat not found"))?;
 
-    let chat = ChatInfo {
-        uuid: chat_model.uuid,
-        name: chat_model.name,
-        is_group: chat_model.is_group,
+    let counts = member_counts(vec![chat_uuid]).await?;
+    let member_count = counts.get(&chat_uuid).copied().unwrap_or_default();
+    let counterpart = if chat_model.kind == sea_orm_active_enums::ChatKind::Private {
+        counterparts(user.uuid, vec![&chat_model])
+            .await?
+            .remove(&chat_uuid)
+    } else {
+        None
     };
+    let last_message = last_messages(vec![chat_uuid]).await?.remove(&chat_uuid);
+    let chat = chat_info(
+        chat_model,
+        &membership,
+        member_count,
+        counterpart,
+        last_message,
+    );
 
     let response = GetChatResponse(chat);
     Ok(Json(response).into_response())
 }
 
+async fn find_private_chat(
+    first_uuid: Uuid,
+    second_uuid: Uuid,
+) -> Result<Option<chats::Model>, AppError> {
+    let chat = Chats::find()
+        .filter(chats::Column::PrivateFirstUuid.eq(first_uuid))
+        .filter(chats::Column::PrivateSecondUuid.eq(second_uuid))
+        .one(db().await)
+        .await
+        .context("Failed to query existing private chat from database")?;
+
+    Ok(chat)
+}
+
 pub async fn verify_private_chat(
-    headers: HeaderMap,
+    AuthUser(user): AuthUser,
     Json(body): Json<VerifyPrivateChatRequest>,
 ) -> Result<Response, AppError> {
-    let user = verify_jwt(&headers).await?;
     let db = db().await;
 
     let other_user: users::Model = Users::find()
         .filter(users::Column::Uuid.eq(body.with_user))
+        .filter(users::Column::DeletedAt.is_null())
+        .filter(users::Column::BannedAt.is_null())
         .one(db)
         .await
         .context("Failed to query user from database")?
@@ -102,45 +322,67 @@ pub async fn verify_private_chat(
         return Err(anyhow!("Cannot create a private chat with yourself").into());
     }
 
-    let existing_chat = chats::Entity::find()
-        .join(JoinType::InnerJoin, chats::Relation::ChatMembers.def())
-        .filter(chats::Column::IsGroup.eq(false))
-        .filter(chat_members::Column::UserUuid.eq(user.uuid))
-        .filter(
-            chat_members::Column::ChatUuid.in_subquery(
-                Query::select()
-                    .column(chat_members::Column::ChatUuid)
-                    .from(chat_members::Entity)
-                    .and_where(chat_members::Column::UserUuid.eq(other_user.uuid))
-                    .to_owned(),
-            ),
-        )
-        .one(db)
-        .await
-        .context("Failed to query existing private chat from database")?;
+    if is_blocked(user.uuid, other_user.uuid).await? {
+        return Err(AppError::forbidden(ForbiddenReason::UserBlocked));
+    }
+
+    let (first_uuid, second_uuid) = if user.uuid < other_user.uuid {
+        (user.uuid, other_user.uuid)
+    } else {
+        (other_user.uuid, user.uuid)
+    };
 
-    if let Some(chat) = existing_chat {
+    if let Some(chat) = find_private_chat(first_uuid, second_uuid).await? {
         let response = NewChatResponse(chat.uuid);
         return Ok(Json(response).into_response());
     }
 
+    if !audience_allows(&other_user.dm_privacy, other_user.uuid, user.uuid).await? {
+        return Err(AppError::forbidden(
+            ForbiddenReason::DirectMessagesRestricted,
+        ));
+    }
+
+    let txn = db
+        .begin()
+        .await
+        .context("Failed to start database transaction")?;
+
     let new_chat_model = chats::ActiveModel {
-        name: Set(format!("{} & {}", user.nickname, other_user.nickname)),
-        is_group: Set(false),
+        name: Set(String::new()),
+        kind: Set(sea_orm_active_enums::ChatKind::Private),
+        private_first_uuid: Set(Some(first_uuid)),
+        private_second_uuid: Set(Some(second_uuid)),
         ..Default::default()
     };
-    let new_chat = new_chat_model
-        .insert(db)
-        .await
-        .context("Failed to create new chat in database")?;
+    let new_chat = match new_chat_model.insert(&txn).await {
+        Ok(new_chat) => new_chat,
+        Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+            txn.rollback()
+                .await
+                .context("Failed to roll back database transaction")?;
+
+            let chat = find_private_chat(first_uuid, second_uuid)
+                .await?
+                .ok_or_else(|| anyhow!("Private chat not found"))?;
+            let response = NewChatResponse(chat.uuid);
+            return Ok(Json(response).into_response());
+        }
+        Err(e) => {
+            return Err(anyhow!(e)
+                .context("Failed to create new chat in database")
+                .into())
+        }
+    };
 
     let first_chat_member_model = chat_members::ActiveModel {
         chat_uuid: Set(new_chat.uuid),
         user_uuid: Set(user.uuid),
         joined_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
     };
-    first_chat_member_model
-        .insert(db)
+    let first_membership = first_chat_member_model
+        .insert(&txn)
         .await
         .context("Failed to add first user to new chat in database")?;
 
@@ -148,53 +390,211 @@ pub async fn verify_private_chat(
         chat_uuid: Set(new_chat.uuid),
         user_uuid: Set(other_user.uuid),
         joined_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
     };
-    second_chat_member_model
-        .insert(db)
+    let second_membership = second_chat_member_model
+        .insert(&txn)
         .await
         .context("Failed to add second user to new chat in database")?;
 
+    txn.commit()
+        .await
+        .context("Failed to commit database transaction")?;
+
+    for (membership, counterpart) in [
+        (first_membership, other_user.clone()),
+        (second_membership, user.clone()),
+    ] {
+        let update = Update::NewChat(chat_info(
+            new_chat.clone(),
+            &membership,
+            2,
+            Some(counterpart),
+            None,
+        ));
+        if let Err(e) = publish(&format!("user_{}", membership.user_uuid), update).await {
+            tracing::error!("Failed to publish new chat: {:?}", e);
+        }
+    }
+
     let response = NewChatResponse(new_chat.uuid);
     Ok(Json(response).into_response())
 }
 
 pub async fn new_group(
-    headers: HeaderMap,
+    AuthUser(user): AuthUser,
     Json(body): Json<NewGroupRequest>,
 ) -> Result<Response, AppError> {
-    let _ = verify_jwt(&headers).await?;
     let db = db().await;
 
+    if body.kind == data::ChatKind::Private {
+        return Err(anyhow!("Private chats cannot be created as groups").into());
+    }
+
+    let title = validate_group_title(&body.title).map_err(AppError::validation)?;
+
+    let txn = db
+        .begin()
+        .await
+        .context("Failed to start database transaction")?;
+
     let new_chat_model = chats::ActiveModel {
-        name: Set(body.title),
-        is_group: Set(true),
+        name: Set(title),
+        kind: Set(body.kind.into()),
         ..Default::default()
     };
     let new_chat = new_chat_model
-        .insert(db)
+        .insert(&txn)
         .await
         .context("Failed to create new chat in database")?;
 
+    let owner_model = chat_members::ActiveModel {
+        chat_uuid: Set(new_chat.uuid),
+        user_uuid: Set(user.uuid),
+        joined_at: Set(chrono::Utc::now().naive_utc()),
+        role: Set(sea_orm_active_enums::ChatRole::Owner),
+        ..Default::default()
+    };
+    let owner_membership = owner_model
+        .insert(&txn)
+        .await
+        .context("Failed to add owner to new chat in database")?;
+
+    let mut memberships = vec![owner_membership];
     for member_uuid in body.members {
+        if memberships.iter().any(|cm| cm.user_uuid == member_uuid) {
+            continue;
+        }
+
         let member_user: Option<users::Model> = Users::find()
             .filter(users::Column::Uuid.eq(member_uuid))
-            .one(db)
+            .filter(users::Column::DeletedAt.is_null())
+            .filter(users::Column::BannedAt.is_null())
+            .one(&txn)
             .await
             .context("Failed to query user from database")?;
 
         if let Some(member_user) = member_user {
+            if is_blocked(user.uuid, member_user.uuid).await? {
+                return Err(AppError::forbidden(ForbiddenReason::UserBlocked));
+            }
+
+            if !audience_allows(&member_user.dm_privacy, member_user.uuid, user.uuid).await? {
+                return Err(AppError::forbidden(ForbiddenReason::GroupInvitesRestricted));
+            }
+
             let chat_member_model = chat_members::ActiveModel {
                 chat_uuid: Set(new_chat.uuid),
                 user_uuid: Set(member_user.uuid),
                 joined_at: Set(chrono::Utc::now().naive_utc()),
+                ..Default::default()
             };
-            chat_member_model
-                .insert(db)
+            let membership = chat_member_model
+                .insert(&txn)
                 .await
                 .context("Failed to add user to new chat in database")?;
+            memberships.push(membership);
+        }
+    }
+
+    txn.commit()
+        .await
+        .context("Failed to commit database transaction")?;
+
+    let member_count = memberships.len() as u64;
+    for membership in memberships {
+        let update = Update::NewChat(chat_info(
+            new_chat.clone(),
+            &membership,
+            member_count,
+            None,
+            None,
+        ));
+        if let Err(e) = publish(&format!("user_{}", membership.user_uuid), update).await {
+            tracing::error!("Failed to publish new chat: {:?}", e);
         }
     }
 
     let response = NewChatResponse(new_chat.uuid);
     Ok(Json(response).into_response())
 }
+
+pub async fn set_chat_preferences(
+    AuthUser(user): AuthUser,
+    Json(body): Json<SetChatPreferencesRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let membership: chat_members::Model = ChatMembers::find()
+        .filter(chat_members::Column::ChatUuid.eq(body.chat_uuid))
+        .filter(chat_members::Column::UserUuid.eq(user.uuid))
+        .one(db)
+        .await
+        .context("Failed to query chat membership from database")?
+        .ok_or_else(|| anyhow!("User is not a member of this chat"))?;
+
+    let mut membership_active: chat_members::ActiveModel = membership.into();
+    membership_active.muted_until = Set(body.preferences.muted_until);
+    membership_active.pinned = Set(body.preferences.pinned);
+    membership_active.archived = Set(body.preferences.archived);
+    let membership = membership_active
+        .update(db)
+        .await
+        .context("Failed to update chat preferences in database")?;
+
+    let update = Update::UpdateChatPreferences(UpdateChatPreferencesPayload {
+        chat_uuid: membership.chat_uuid,
+        preferences: chat_preferences(&membership),
+    });
+    if let Err(e) = publish(&format!("user_{}", user.uuid), update).await {
+        tracing::error!("Failed to publish chat preferences: {:?}", e);
+    }
+
+    let response = SetChatPreferencesResponse {};
+    Ok(Json(response).into_response())
+}
+
+pub async fn set_slow_mode(
+    AuthUser(user): AuthUser,
+    Json(body): Json<SetSlowModeRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let (membership, chat): (chat_members::Model, Option<chats::Model>) = ChatMembers::find()
+        .filter(chat_members::Column::ChatUuid.eq(body.chat_uuid))
+        .filter(chat_members::Column::UserUuid.eq(user.uuid))
+        .find_also_related(Chats)
+        .one(db)
+        .await
+        .context("Failed to query chat membership from database")?
+        .ok_or_else(|| anyhow!("User is not a member of this chat"))?;
+    let chat = chat.ok_or_else(|| anyhow!("Chat not found"))?;
+
+    if chat.kind == sea_orm_active_enums::ChatKind::Private {
+        return Err(anyhow!("Slow mode is only available in groups").into());
+    }
+
+    if membership.role == sea_orm_active_enums::ChatRole::Member {
+        return Err(anyhow!("Only admins can change slow mode").into());
+    }
+
+    let seconds = validate_slow_mode(body.seconds).map_err(AppError::validation)?;
+
+    let mut chat_active: chats::ActiveModel = chat.into();
+    chat_active.slow_mode_seconds = Set(seconds.map(|seconds| seconds as i32));
+    let chat = chat_active
+        .update(db)
+        .await
+        .context("Failed to update slow mode in database")?;
+
+    let update = Update::SlowModeChanged(SlowModeChangedPayload {
+        chat_uuid: chat.uuid,
+        slow_mode: seconds,
+    });
+    if let Err(e) = publish(&format!("chat_{}", chat.uuid), update).await {
+        tracing::error!("Failed to publish slow mode: {:?}", e);
+    }
+
+    let response = SetSlowModeResponse {};
+    Ok(Json(response).into_response())
+}
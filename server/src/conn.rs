@@ -1,6 +1,6 @@
 use anyhow::anyhow;
 use async_once_cell::OnceCell;
-use sea_orm::{Database, DatabaseConnection};
+use sea_orm::{prelude::Uuid, Database, DatabaseConnection};
 
 use crate::AppError;
 use utils::{
@@ -44,3 +44,48 @@ pub async fn publish(channel: &str, update: Update) -> Result<(), AppError> {
         Err(anyhow!("Failed to publish message: {}", text).into())
     }
 }
+
+pub async fn disconnect(user_uuid: Uuid) -> Result<(), AppError> {
+    let body = serde_json::json!({
+        "user": user_uuid.to_string(),
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .post(format!("{}/api/disconnect", centrifugo_url()))
+        .header("Authorization", format!("apikey {}", centrifugo_key()))
+        .json(&body)
+        .send()
+        .await?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        let text = res.text().await?;
+        Err(anyhow!("Failed to disconnect user: {}", text).into())
+    }
+}
+
+pub async fn unsubscribe(user_uuid: Uuid, channel: &str) -> Result<(), AppError> {
+    let body = serde_json::json!({
+        "user": user_uuid.to_string(),
+        "channel": channel,
+    });
+
+    let client = reqwest::Client::new();
+
+    let res = client
+        .post(format!("{}/api/unsubscribe", centrifugo_url()))
+        .header("Authorization", format!("apikey {}", centrifugo_key()))
+        .json(&body)
+        .send()
+        .await?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        let text = res.text().await?;
+        Err(anyhow!("Failed to unsubscribe user: {}", text).into())
+    }
+}
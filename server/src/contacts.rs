@@ -0,0 +1,132 @@
+use anyhow::{anyhow, Context};
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use sea_orm::{
+    prelude::Uuid, sea_query::OnConflict, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter,
+};
+use std::collections::HashMap;
+
+use crate::{db, schema::*, users::user_info, AppError, AuthUser};
+use utils::{
+    data::ContactInfo,
+    requests::{
+        ListContactsResponse, RemoveContactRequest, RemoveContactResponse, SaveContactRequest,
+        SaveContactResponse,
+    },
+    validation::validate_nickname,
+};
+
+fn contact_info(contact: contacts::Model, user: users::Model) -> ContactInfo {
+    ContactInfo {
+        user: user_info(user),
+        nickname: contact.nickname,
+        created_at: contact.created_at,
+    }
+}
+
+pub async fn user_contacts(user_uuid: Uuid) -> Result<Vec<ContactInfo>, AppError> {
+    let db = db().await;
+
+    let contact_models: Vec<contacts::Model> = Contacts::find()
+        .filter(contacts::Column::OwnerUuid.eq(user_uuid))
+        .all(db)
+        .await
+        .context("Failed to query contacts from database")?;
+
+    let mut users: HashMap<Uuid, users::Model> = Users::find()
+        .filter(
+            users::Column::Uuid.is_in(
+                contact_models
+                    .iter()
+                    .map(|contact| contact.contact_uuid)
+                    .collect::<Vec<_>>(),
+            ),
+        )
+        .all(db)
+        .await
+        .context("Failed to query users from database")?
+        .into_iter()
+        .map(|user| (user.uuid, user))
+        .collect();
+
+    let mut contacts: Vec<ContactInfo> = contact_models
+        .into_iter()
+        .filter_map(|contact| {
+            let user = users.remove(&contact.contact_uuid)?;
+            Some(contact_info(contact, user))
+        })
+        .collect();
+    contacts.sort_by_key(|contact| contact.display_name().to_lowercase());
+
+    Ok(contacts)
+}
+
+pub async fn list_contacts(AuthUser(user): AuthUser) -> Result<Response, AppError> {
+    let contacts = user_contacts(user.uuid).await?;
+
+    let response = ListContactsResponse(contacts);
+    Ok(Json(response).into_response())
+}
+
+pub async fn save_contact(
+    AuthUser(user): AuthUser,
+    Json(body): Json<SaveContactRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    if body.user_uuid == user.uuid {
+        return Err(anyhow!("Cannot add yourself as a contact").into());
+    }
+
+    let nickname = body
+        .nickname
+        .filter(|nickname| !nickname.trim().is_empty())
+        .map(|nickname| validate_nickname(&nickname))
+        .transpose()
+        .map_err(AppError::validation)?;
+
+    let contact_user: users::Model = Users::find_by_id(body.user_uuid)
+        .filter(users::Column::DeletedAt.is_null())
+        .one(db)
+        .await
+        .context("Failed to query user from database")?
+        .ok_or_else(|| anyhow!("User not found"))?;
+
+    let new_contact = contacts::ActiveModel {
+        owner_uuid: Set(user.uuid),
+        contact_uuid: Set(contact_user.uuid),
+        nickname: Set(nickname),
+        ..Default::default()
+    };
+    let contact = Contacts::insert(new_contact)
+        .on_conflict(
+            OnConflict::columns([contacts::Column::OwnerUuid, contacts::Column::ContactUuid])
+                .update_column(contacts::Column::Nickname)
+                .to_owned(),
+        )
+        .exec_with_returning(db)
+        .await
+        .context("Failed to save contact in database")?;
+
+    let response = SaveContactResponse(contact_info(contact, contact_user));
+    Ok(Json(response).into_response())
+}
+
+pub async fn remove_contact(
+    AuthUser(user): AuthUser,
+    Json(body): Json<RemoveContactRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    Contacts::delete_many()
+        .filter(contacts::Column::OwnerUuid.eq(user.uuid))
+        .filter(contacts::Column::ContactUuid.eq(body.0))
+        .exec(db)
+        .await
+        .context("Failed to delete contact from database")?;
+
+    let response = RemoveContactResponse {};
+    Ok(Json(response).into_response())
+}
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.17
 
+use super::sea_orm_active_enums::ChatRole;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -10,6 +11,10 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub user_uuid: Uuid,
     pub joined_at: DateTime,
+    pub muted_until: Option<DateTime>,
+    pub pinned: Option<i32>,
+    pub archived: bool,
+    pub role: ChatRole,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.17
 
+use super::sea_orm_active_enums::ChatKind;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -8,8 +9,11 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub uuid: Uuid,
     pub name: String,
-    pub is_group: bool,
     pub created_at: DateTime,
+    pub kind: ChatKind,
+    pub private_first_uuid: Option<Uuid>,
+    pub private_second_uuid: Option<Uuid>,
+    pub slow_mode_seconds: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -18,6 +22,8 @@ pub enum Relation {
     ChatMembers,
     #[sea_orm(has_many = "super::messages::Entity")]
     Messages,
+    #[sea_orm(has_many = "super::moderation_actions::Entity")]
+    ModerationActions,
 }
 
 impl Related<super::chat_members::Entity> for Entity {
@@ -32,6 +38,12 @@ impl Related<super::messages::Entity> for Entity {
     }
 }
 
+impl Related<super::moderation_actions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ModerationActions.def()
+    }
+}
+
 impl Related<super::users::Entity> for Entity {
     fn to() -> RelationDef {
         super::chat_members::Relation::Users.def()
@@ -27,6 +27,8 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Chats,
+    #[sea_orm(has_many = "super::reports::Entity")]
+    Reports,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::SenderUuid",
@@ -43,6 +45,12 @@ impl Related<super::chats::Entity> for Entity {
     }
 }
 
+impl Related<super::reports::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Reports.def()
+    }
+}
+
 impl Related<super::users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Users.def()
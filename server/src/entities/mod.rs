@@ -2,7 +2,18 @@
 
 pub mod prelude;
 
+pub mod access_tokens;
+pub mod avatars;
+pub mod chat_folder_chats;
+pub mod chat_folders;
 pub mod chat_members;
 pub mod chats;
+pub mod contacts;
 pub mod messages;
+pub mod moderation_actions;
+pub mod reports;
+pub mod sea_orm_active_enums;
+pub mod sessions;
+pub mod user_blocks;
+pub mod username_redirects;
 pub mod users;
@@ -1,6 +1,16 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.17
 
+pub use super::access_tokens::Entity as AccessTokens;
+pub use super::avatars::Entity as Avatars;
+pub use super::chat_folder_chats::Entity as ChatFolderChats;
+pub use super::chat_folders::Entity as ChatFolders;
 pub use super::chat_members::Entity as ChatMembers;
 pub use super::chats::Entity as Chats;
+pub use super::contacts::Entity as Contacts;
 pub use super::messages::Entity as Messages;
+pub use super::moderation_actions::Entity as ModerationActions;
+pub use super::reports::Entity as Reports;
+pub use super::sessions::Entity as Sessions;
+pub use super::user_blocks::Entity as UserBlocks;
+pub use super::username_redirects::Entity as UsernameRedirects;
 pub use super::users::Entity as Users;
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.17
 
+use super::sea_orm_active_enums::PrivacyAudience;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -16,6 +17,21 @@ pub struct Model {
     #[sea_orm(column_type = "Text")]
     pub nickname: String,
     pub created_at: DateTime,
+    pub is_moderator: bool,
+    pub banned_at: Option<DateTime>,
+    pub last_seen_at: Option<DateTime>,
+    pub deleted_at: Option<DateTime>,
+    pub avatar_updated_at: Option<DateTime>,
+    #[sea_orm(column_type = "Text")]
+    pub bio: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub status_emoji: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub status_text: Option<String>,
+    pub status_expires_at: Option<DateTime>,
+    pub dm_privacy: PrivacyAudience,
+    pub last_seen_privacy: PrivacyAudience,
+    pub discoverable: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
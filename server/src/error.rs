@@ -1,14 +1,34 @@
 use axum::{
-    http::StatusCode,
+    http::{header::RETRY_AFTER, StatusCode},
     response::{IntoResponse, Response},
+    Json,
+};
+use utils::{
+    errors::{ApiError, AuthReason, ForbiddenReason, Resource},
+    validation::ValidationError,
 };
 
 #[derive(Debug)]
 pub enum AppError {
-    Auth(anyhow::Error),
+    Auth(AuthReason),
+    Api(ApiError),
     Internal(anyhow::Error),
 }
 
+impl AppError {
+    pub fn validation(err: ValidationError) -> Self {
+        AppError::Api(ApiError::Validation(err))
+    }
+
+    pub fn forbidden(reason: ForbiddenReason) -> Self {
+        AppError::Api(ApiError::Forbidden { reason })
+    }
+
+    pub fn not_found(resource: Resource) -> Self {
+        AppError::Api(ApiError::NotFound { resource })
+    }
+}
+
 impl<E: Into<anyhow::Error>> From<E> for AppError {
     fn from(err: E) -> Self {
         AppError::Internal(err.into())
@@ -18,9 +38,32 @@ impl<E: Into<anyhow::Error>> From<E> for AppError {
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         match self {
-            AppError::Auth(e) => {
-                eprintln!("Auth error: {e:?}");
-                (StatusCode::UNAUTHORIZED, "Unauthorized").into_response()
+            AppError::Auth(reason) => {
+                let status = match reason {
+                    AuthReason::InsufficientScope | AuthReason::ChannelForbidden => {
+                        StatusCode::FORBIDDEN
+                    }
+                    _ => StatusCode::UNAUTHORIZED,
+                };
+                (status, Json(ApiError::Unauthorized { reason })).into_response()
+            }
+            AppError::Api(e) => {
+                let status = match e {
+                    ApiError::SlowMode { .. } | ApiError::RateLimited { .. } => {
+                        StatusCode::TOO_MANY_REQUESTS
+                    }
+                    ApiError::Validation(_) => StatusCode::BAD_REQUEST,
+                    ApiError::Unauthorized { .. } => StatusCode::UNAUTHORIZED,
+                    ApiError::Forbidden { .. } => StatusCode::FORBIDDEN,
+                    ApiError::NotFound { .. } => StatusCode::NOT_FOUND,
+                };
+                let mut response = (status, Json(&e)).into_response();
+                if let Some(retry_after) = e.retry_after() {
+                    response
+                        .headers_mut()
+                        .insert(RETRY_AFTER, retry_after.into());
+                }
+                response
             }
             AppError::Internal(e) => {
                 eprintln!("Internal error: {e:?}");
@@ -0,0 +1,200 @@
+use anyhow::{anyhow, Context};
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use sea_orm::{
+    prelude::Uuid, ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+
+use crate::{conn::publish, db, schema::*, AppError, AuthUser};
+use utils::{
+    data::{ChatFolder, FolderFilters},
+    requests::{
+        DeleteFolderRequest, DeleteFolderResponse, ListFoldersResponse, SaveFolderRequest,
+        SaveFolderResponse,
+    },
+    updates::Update,
+    validation::validate_folder_name,
+};
+
+fn chat_folder(folder: chat_folders::Model, include: Vec<Uuid>) -> ChatFolder {
+    ChatFolder {
+        uuid: folder.uuid,
+        name: folder.name,
+        include_all: folder.include_all,
+        include,
+        filters: FolderFilters {
+            groups_only: folder.groups_only,
+            unread_only: folder.unread_only,
+            exclude_muted: folder.exclude_muted,
+        },
+    }
+}
+
+pub async fn user_folders(user_uuid: Uuid) -> Result<Vec<ChatFolder>, AppError> {
+    let folders: Vec<(chat_folders::Model, Vec<chat_folder_chats::Model>)> = ChatFolders::find()
+        .filter(chat_folders::Column::UserUuid.eq(user_uuid))
+        .order_by_asc(chat_folders::Column::Position)
+        .order_by_asc(chat_folders::Column::CreatedAt)
+        .find_with_related(ChatFolderChats)
+        .all(db().await)
+        .await
+        .context("Failed to query chat folders from database")?;
+
+    Ok(folders
+        .into_iter()
+        .map(|(folder, chats)| {
+            let include = chats.into_iter().map(|c| c.chat_uuid).collect();
+            chat_folder(folder, include)
+        })
+        .collect())
+}
+
+async fn publish_folders(user_uuid: Uuid) -> Result<(), AppError> {
+    let folders = user_folders(user_uuid).await?;
+    publish(
+        &format!("user_{}", user_uuid),
+        Update::UpdateFolders(folders),
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn list_folders(AuthUser(user): AuthUser) -> Result<Response, AppError> {
+    let folders = user_folders(user.uuid).await?;
+
+    let response = ListFoldersResponse(folders);
+    Ok(Json(response).into_response())
+}
+
+pub async fn save_folder(
+    AuthUser(user): AuthUser,
+    Json(body): Json<SaveFolderRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let name = validate_folder_name(&body.name).map_err(AppError::validation)?;
+
+    let memberships: Vec<chat_members::Model> = ChatMembers::find()
+        .filter(chat_members::Column::UserUuid.eq(user.uuid))
+        .filter(chat_members::Column::ChatUuid.is_in(body.include.clone()))
+        .all(db)
+        .await
+        .context("Failed to query chat memberships from database")?;
+
+    let txn = db
+        .begin()
+        .await
+        .context("Failed to start database transaction")?;
+
+    let folder = match body.uuid {
+        Some(folder_uuid) => {
+            let folder: chat_folders::Model = ChatFolders::find()
+                .filter(chat_folders::Column::Uuid.eq(folder_uuid))
+                .filter(chat_folders::Column::UserUuid.eq(user.uuid))
+                .one(&txn)
+                .await
+                .context("Failed to query chat folder from database")?
+                .ok_or_else(|| anyhow!("Folder not found"))?;
+
+            let mut folder_active: chat_folders::ActiveModel = folder.into();
+            folder_active.name = Set(name);
+            folder_active.include_all = Set(body.include_all);
+            folder_active.groups_only = Set(body.filters.groups_only);
+            folder_active.unread_only = Set(body.filters.unread_only);
+            folder_active.exclude_muted = Set(body.filters.exclude_muted);
+            let folder = folder_active
+                .update(&txn)
+                .await
+                .context("Failed to update chat folder in database")?;
+
+            ChatFolderChats::delete_many()
+                .filter(chat_folder_chats::Column::FolderUuid.eq(folder.uuid))
+                .exec(&txn)
+                .await
+                .context("Failed to clear chat folder chats in database")?;
+
+            folder
+        }
+        None => {
+            let last_folder: Option<chat_folders::Model> = ChatFolders::find()
+                .filter(chat_folders::Column::UserUuid.eq(user.uuid))
+                .order_by_desc(chat_folders::Column::Position)
+                .one(&txn)
+                .await
+                .context("Failed to query chat folders from database")?;
+
+            let new_folder = chat_folders::ActiveModel {
+                user_uuid: Set(user.uuid),
+                name: Set(name),
+                position: Set(last_folder.map_or(0, |f| f.position + 1)),
+                include_all: Set(body.include_all),
+                groups_only: Set(body.filters.groups_only),
+                unread_only: Set(body.filters.unread_only),
+                exclude_muted: Set(body.filters.exclude_muted),
+                ..Default::default()
+            };
+            new_folder
+                .insert(&txn)
+                .await
+                .context("Failed to insert chat folder into database")?
+        }
+    };
+
+    let include: Vec<Uuid> = if body.include_all {
+        Vec::new()
+    } else {
+        memberships.into_iter().map(|cm| cm.chat_uuid).collect()
+    };
+    if !include.is_empty() {
+        let folder_chats = include
+            .iter()
+            .map(|chat_uuid| chat_folder_chats::ActiveModel {
+                folder_uuid: Set(folder.uuid),
+                chat_uuid: Set(*chat_uuid),
+            });
+        ChatFolderChats::insert_many(folder_chats)
+            .exec(&txn)
+            .await
+            .context("Failed to insert chat folder chats into database")?;
+    }
+
+    txn.commit()
+        .await
+        .context("Failed to commit database transaction")?;
+
+    if let Err(e) = publish_folders(user.uuid).await {
+        tracing::error!("Failed to publish folders: {:?}", e);
+    }
+
+    let response = SaveFolderResponse(chat_folder(folder, include));
+    Ok(Json(response).into_response())
+}
+
+pub async fn delete_folder(
+    AuthUser(user): AuthUser,
+    Json(body): Json<DeleteFolderRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let result = ChatFolders::delete_many()
+        .filter(chat_folders::Column::Uuid.eq(body.0))
+        .filter(chat_folders::Column::UserUuid.eq(user.uuid))
+        .exec(db)
+        .await
+        .context("Failed to delete chat folder from database")?;
+
+    if result.rows_affected == 0 {
+        return Err(anyhow!("Folder not found").into());
+    }
+
+    if let Err(e) = publish_folders(user.uuid).await {
+        tracing::error!("Failed to publish folders: {:?}", e);
+    }
+
+    let response = DeleteFolderResponse {};
+    Ok(Json(response).into_response())
+}
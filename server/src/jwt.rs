@@ -1,83 +1,237 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{Context, Result};
 use axum::{
-    http::HeaderMap,
+    extract::FromRequestParts,
+    http::{request::Parts, HeaderMap},
     response::{IntoResponse, Response},
     Json,
 };
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use chrono::{Duration, NaiveDateTime, Utc};
-use sea_orm::{prelude::Uuid, ColumnTrait, EntityTrait, QueryFilter};
+use rand::RngCore;
+use sea_orm::{
+    prelude::Uuid, sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition,
+    ConnectionTrait, EntityTrait, QueryFilter,
+};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
-use crate::{db, schema::*, verify_kratos_cookie, AppError};
+use crate::{
+    access_tokens::{is_access_token, verify_access_token, verify_access_token_unscoped},
+    db,
+    schema::*,
+    signing, AppError, KratosSession,
+};
 use utils::{
     config::server::{centrifugo_jwt_secret, jwt_secret},
-    requests::{GenerateJwtResponse, VerifyJwtResponse},
+    data::TokenScope,
+    errors::AuthReason,
+    requests::{
+        GenerateCentrifugoSubscriptionRequest, GenerateJwtResponse, RefreshJwtRequest,
+        RevokeSessionRequest, RevokeSessionResponse, SessionTokensResponse, VerifyJwtResponse,
+    },
 };
 
+const ACCESS_TOKEN_MINUTES: i64 = 15;
+const REFRESH_TOKEN_DAYS: i64 = 30;
+const SESSION_SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+const API_AUDIENCE: &str = "commeator-api";
+const CENTRIFUGO_AUDIENCE: &str = "centrifugo";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct Claims {
     pub sub: Uuid,
     pub exp: i64,
+    pub jti: Uuid,
+    pub aud: String,
 }
 
-pub async fn verify_jwt(headers: &HeaderMap) -> Result<users::Model> {
-    let token = headers
+pub fn check_user(user: &users::Model) -> Result<(), AuthReason> {
+    if user.banned_at.is_some() {
+        return Err(AuthReason::UserBanned);
+    }
+    if user.deleted_at.is_some() {
+        return Err(AuthReason::UserDeleted);
+    }
+
+    Ok(())
+}
+
+fn check_session(session: &sessions::Model) -> Result<(), AuthReason> {
+    if session.revoked_at.is_some() {
+        return Err(AuthReason::SessionRevoked);
+    }
+    if session.expires_at <= Utc::now().naive_utc() {
+        return Err(AuthReason::SessionExpired);
+    }
+
+    Ok(())
+}
+
+fn token_error_reason(err: &anyhow::Error) -> AuthReason {
+    match err
+        .downcast_ref::<jsonwebtoken::errors::Error>()
+        .map(|e| e.kind())
+    {
+        Some(jsonwebtoken::errors::ErrorKind::ExpiredSignature) => AuthReason::ExpiredToken,
+        _ => AuthReason::InvalidToken,
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Result<&str, AppError> {
+    headers
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .and_then(|h| {
-            if h.starts_with("Bearer ") {
-                Some(h.trim_start_matches("Bearer ").to_string())
-            } else {
-                None
-            }
-        })
-        .ok_or_else(|| anyhow!("Missing or invalid Authorization header"))?;
-
-    let claims = jsonwebtoken::decode::<Claims>(
-        token,
-        &jsonwebtoken::DecodingKey::from_secret(&jwt_secret()),
-        &jsonwebtoken::Validation::default(),
-    )
-    .context("Failed to decode JWT")?
-    .claims;
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .ok_or(AppError::Auth(AuthReason::MissingToken))
+}
+
+pub async fn verify_jwt(
+    headers: &HeaderMap,
+    scope: Option<TokenScope>,
+) -> Result<users::Model, AppError> {
+    let token = bearer_token(headers)?;
+
+    if is_access_token(token) {
+        return verify_access_token(token, scope).await;
+    }
+
+    verify_session_jwt(token).await
+}
+
+async fn verify_session_jwt(token: &str) -> Result<users::Model, AppError> {
+    let claims = signing::decode::<Claims>(token, API_AUDIENCE, jwt_secret)
+        .map_err(|e| AppError::Auth(token_error_reason(&e)))?;
 
     let db = db().await;
+    let session = Sessions::find_by_id(claims.jti)
+        .one(db)
+        .await
+        .context("Failed to query session from database")?
+        .filter(|session| session.user_uuid == claims.sub)
+        .ok_or(AppError::Auth(AuthReason::SessionRevoked))?;
+    check_session(&session).map_err(AppError::Auth)?;
+
     let user = Users::find()
         .filter(users::Column::Uuid.eq(claims.sub))
         .one(db)
         .await
         .context("Failed to query user from database")?
-        .ok_or_else(|| anyhow!("User not found"))?;
+        .ok_or(AppError::Auth(AuthReason::UserNotFound))?;
+    check_user(&user).map_err(AppError::Auth)?;
 
     Ok(user)
 }
 
-pub async fn generate(uuid: Uuid) -> Result<(String, NaiveDateTime)> {
-    let expiration = Utc::now() + Duration::hours(24);
+pub struct AuthUser(pub users::Model);
+
+impl<S> FromRequestParts<S> for AuthUser
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let scope = parts.extensions.get::<TokenScope>().copied();
+        verify_jwt(&parts.headers, scope).await.map(AuthUser)
+    }
+}
+
+pub async fn generate(uuid: Uuid, session_uuid: Uuid) -> Result<(String, NaiveDateTime)> {
+    let expiration = Utc::now() + Duration::minutes(ACCESS_TOKEN_MINUTES);
     let claims = Claims {
         sub: uuid,
         exp: expiration.timestamp(),
+        jti: session_uuid,
+        aud: API_AUDIENCE.to_string(),
     };
 
-    let header = jsonwebtoken::Header::default();
-    let token = jsonwebtoken::encode(
-        &header,
-        &claims,
-        &jsonwebtoken::EncodingKey::from_secret(&jwt_secret()),
-    )
-    .context("Failed to encode JWT")?;
+    let token = signing::encode(&claims, jwt_secret).context("Failed to encode JWT")?;
 
     Ok((token, expiration.naive_utc()))
 }
 
-pub async fn endpoint_generate(headers: HeaderMap) -> Result<Response, AppError> {
-    let email = verify_kratos_cookie(&headers)
+pub fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+async fn session_tokens(
+    session: &sessions::Model,
+    refresh_token: String,
+) -> Result<SessionTokensResponse> {
+    let (jwt, expires_at) = generate(session.user_uuid, session.uuid).await?;
+
+    Ok(SessionTokensResponse {
+        jwt,
+        expires_at,
+        refresh_token,
+        refresh_expires_at: session.expires_at,
+    })
+}
+
+pub async fn create_session(user_uuid: Uuid) -> Result<SessionTokensResponse> {
+    let refresh_token = random_token();
+
+    let session_model = sessions::ActiveModel {
+        uuid: Set(Uuid::new_v4()),
+        user_uuid: Set(user_uuid),
+        refresh_token_hash: Set(hash_token(&refresh_token)),
+        expires_at: Set((Utc::now() + Duration::days(REFRESH_TOKEN_DAYS)).naive_utc()),
+        ..Default::default()
+    };
+    let session = session_model
+        .insert(db().await)
+        .await
+        .context("Failed to insert session into database")?;
+
+    session_tokens(&session, refresh_token).await
+}
+
+pub async fn revoke_sessions<C: ConnectionTrait>(db: &C, user_uuid: Uuid) -> Result<()> {
+    Sessions::update_many()
+        .col_expr(
+            sessions::Column::RevokedAt,
+            Expr::value(Utc::now().naive_utc()),
+        )
+        .filter(sessions::Column::UserUuid.eq(user_uuid))
+        .filter(sessions::Column::RevokedAt.is_null())
+        .exec(db)
         .await
-        .context("Failed to verify Kratos cookie")?
-        .identity
-        .traits
-        .email;
+        .context("Failed to revoke sessions in database")?;
+
+    Ok(())
+}
+
+async fn delete_expired_sessions() -> Result<()> {
+    Sessions::delete_many()
+        .filter(sessions::Column::ExpiresAt.lt(Utc::now().naive_utc()))
+        .exec(db().await)
+        .await
+        .context("Failed to delete expired sessions from database")?;
+
+    Ok(())
+}
+
+pub async fn sweep_sessions() {
+    loop {
+        tokio::time::sleep(SESSION_SWEEP_INTERVAL).await;
+
+        if let Err(e) = delete_expired_sessions().await {
+            tracing::error!("Failed to delete expired sessions: {:?}", e);
+        }
+    }
+}
+
+pub async fn endpoint_generate(
+    KratosSession(session): KratosSession,
+) -> Result<Response, AppError> {
+    let email = session.identity.traits.email;
 
     let db = db().await;
     let user = Users::find()
@@ -85,16 +239,115 @@ pub async fn endpoint_generate(headers: HeaderMap) -> Result<Response, AppError>
         .one(db)
         .await
         .context("Failed to query user from database")?
-        .ok_or_else(|| anyhow!("User not found"))?;
+        .ok_or(AppError::Auth(AuthReason::UserNotFound))?;
+    check_user(&user).map_err(AppError::Auth)?;
 
-    let (jwt, expires_at) = generate(user.uuid).await?;
+    let response = create_session(user.uuid).await?;
+    Ok(Json(response).into_response())
+}
 
-    let response = GenerateJwtResponse { jwt, expires_at };
+async fn revoke_session(session_uuid: Uuid) -> Result<()> {
+    Sessions::update_many()
+        .col_expr(
+            sessions::Column::RevokedAt,
+            Expr::value(Utc::now().naive_utc()),
+        )
+        .filter(sessions::Column::Uuid.eq(session_uuid))
+        .filter(sessions::Column::RevokedAt.is_null())
+        .exec(db().await)
+        .await
+        .context("Failed to revoke session in database")?;
+
+    Ok(())
+}
+
+pub async fn endpoint_refresh(Json(body): Json<RefreshJwtRequest>) -> Result<Response, AppError> {
+    let db = db().await;
+    let token_hash = hash_token(&body.refresh_token);
+
+    let session = Sessions::find()
+        .filter(
+            Condition::any()
+                .add(sessions::Column::RefreshTokenHash.eq(token_hash.clone()))
+                .add(sessions::Column::PreviousRefreshTokenHash.eq(token_hash.clone())),
+        )
+        .one(db)
+        .await
+        .context("Failed to query session from database")?
+        .ok_or(AppError::Auth(AuthReason::SessionRevoked))?;
+
+    if session.refresh_token_hash != token_hash {
+        revoke_session(session.uuid).await?;
+        return Err(AppError::Auth(AuthReason::SessionRevoked));
+    }
+    check_session(&session).map_err(AppError::Auth)?;
+
+    let user = Users::find_by_id(session.user_uuid)
+        .one(db)
+        .await
+        .context("Failed to query user from database")?
+        .ok_or(AppError::Auth(AuthReason::UserNotFound))?;
+    check_user(&user).map_err(AppError::Auth)?;
+
+    let refresh_token = random_token();
+    let now = Utc::now().naive_utc();
+    let expires_at = now + Duration::days(REFRESH_TOKEN_DAYS);
+    let result = Sessions::update_many()
+        .col_expr(
+            sessions::Column::RefreshTokenHash,
+            Expr::value(hash_token(&refresh_token)),
+        )
+        .col_expr(
+            sessions::Column::PreviousRefreshTokenHash,
+            Expr::value(token_hash.clone()),
+        )
+        .col_expr(sessions::Column::RefreshedAt, Expr::value(now))
+        .col_expr(sessions::Column::ExpiresAt, Expr::value(expires_at))
+        .filter(sessions::Column::Uuid.eq(session.uuid))
+        .filter(sessions::Column::RefreshTokenHash.eq(token_hash))
+        .filter(sessions::Column::RevokedAt.is_null())
+        .exec(db)
+        .await
+        .context("Failed to rotate refresh token in database")?;
+
+    if result.rows_affected != 1 {
+        revoke_session(session.uuid).await?;
+        return Err(AppError::Auth(AuthReason::SessionRevoked));
+    }
+
+    let session = sessions::Model {
+        refreshed_at: now,
+        expires_at,
+        ..session
+    };
+    let response = session_tokens(&session, refresh_token).await?;
+    Ok(Json(response).into_response())
+}
+
+pub async fn endpoint_revoke(Json(body): Json<RevokeSessionRequest>) -> Result<Response, AppError> {
+    let db = db().await;
+
+    Sessions::update_many()
+        .col_expr(
+            sessions::Column::RevokedAt,
+            Expr::value(Utc::now().naive_utc()),
+        )
+        .filter(sessions::Column::RefreshTokenHash.eq(hash_token(&body.refresh_token)))
+        .filter(sessions::Column::RevokedAt.is_null())
+        .exec(db)
+        .await
+        .context("Failed to revoke session in database")?;
+
+    let response = RevokeSessionResponse {};
     Ok(Json(response).into_response())
 }
 
 pub async fn endpoint_verify(headers: HeaderMap) -> Result<Response, AppError> {
-    let correct = verify_jwt(&headers).await.is_ok();
+    let correct = match bearer_token(&headers) {
+        Ok(token) if is_access_token(token) => verify_access_token_unscoped(token).await.is_ok(),
+        Ok(token) => verify_session_jwt(token).await.is_ok(),
+        Err(_) => false,
+    };
 
     let response = VerifyJwtResponse(correct);
     Ok(Json(response).into_response())
@@ -104,46 +357,87 @@ pub async fn endpoint_verify(headers: HeaderMap) -> Result<Response, AppError> {
 pub struct CentrifugoClaims {
     pub sub: Uuid,
     pub exp: i64,
-    pub channels: Vec<String>,
+    pub aud: String,
 }
 
-pub async fn generate_centrifugo_token(
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CentrifugoSubscriptionClaims {
+    pub sub: Uuid,
+    pub exp: i64,
+    pub aud: String,
+    pub channel: String,
+}
+
+pub async fn generate_centrifugo_token(uuid: Uuid) -> Result<(String, NaiveDateTime)> {
+    let expiration = Utc::now() + Duration::minutes(15);
+    let claims = CentrifugoClaims {
+        sub: uuid,
+        exp: expiration.timestamp(),
+        aud: CENTRIFUGO_AUDIENCE.to_string(),
+    };
+
+    let jwt = signing::encode(&claims, centrifugo_jwt_secret)
+        .context("Failed to encode Centrifugo JWT")?;
+
+    Ok((jwt, expiration.naive_utc()))
+}
+
+pub async fn generate_centrifugo_subscription_token(
     uuid: Uuid,
-    channels: Vec<String>,
+    channel: String,
 ) -> Result<(String, NaiveDateTime)> {
     let expiration = Utc::now() + Duration::minutes(15);
-    let claims = CentrifugoClaims {
+    let claims = CentrifugoSubscriptionClaims {
         sub: uuid,
         exp: expiration.timestamp(),
-        channels,
+        aud: CENTRIFUGO_AUDIENCE.to_string(),
+        channel,
     };
 
-    let header = jsonwebtoken::Header::default();
-    let jwt = jsonwebtoken::encode(
-        &header,
-        &claims,
-        &jsonwebtoken::EncodingKey::from_secret(&centrifugo_jwt_secret()), // same secret, or a separate one
-    )
-    .context("Failed to encode Centrifugo JWT")?;
+    let jwt = signing::encode(&claims, centrifugo_jwt_secret)
+        .context("Failed to encode Centrifugo subscription JWT")?;
 
     Ok((jwt, expiration.naive_utc()))
 }
 
-pub async fn endpoint_generate_centrifugo(headers: HeaderMap) -> Result<Response, AppError> {
-    let user = verify_jwt(&headers).await.context("Failed to verify JWT")?;
+pub async fn endpoint_generate_centrifugo(AuthUser(user): AuthUser) -> Result<Response, AppError> {
+    let (jwt, expires_at) = generate_centrifugo_token(user.uuid).await?;
 
-    let chats_member = chat_members::Entity::find()
-        .filter(chat_members::Column::UserUuid.eq(user.uuid))
-        .all(db().await)
-        .await
-        .context("Failed to query chat memberships from database")?;
+    let response = GenerateJwtResponse { jwt, expires_at };
+    Ok(Json(response).into_response())
+}
+
+async fn can_subscribe(user: &users::Model, channel: &str) -> Result<bool> {
+    if let Some(user_uuid) = channel.strip_prefix("user_") {
+        return Ok(Uuid::parse_str(user_uuid).is_ok_and(|user_uuid| user_uuid == user.uuid));
+    }
+
+    if let Some(chat_uuid) = channel.strip_prefix("chat_") {
+        let Ok(chat_uuid) = Uuid::parse_str(chat_uuid) else {
+            return Ok(false);
+        };
+        let is_member = chat_members::Entity::find()
+            .filter(chat_members::Column::ChatUuid.eq(chat_uuid))
+            .filter(chat_members::Column::UserUuid.eq(user.uuid))
+            .one(db().await)
+            .await
+            .context("Failed to query chat membership from database")?
+            .is_some();
+        return Ok(is_member);
+    }
+
+    Ok(false)
+}
 
-    let channels = chats_member
-        .into_iter()
-        .map(|cm| format!("chat_{}", cm.chat_uuid))
-        .collect();
+pub async fn endpoint_generate_centrifugo_subscription(
+    AuthUser(user): AuthUser,
+    Json(body): Json<GenerateCentrifugoSubscriptionRequest>,
+) -> Result<Response, AppError> {
+    if !can_subscribe(&user, &body.channel).await? {
+        return Err(AppError::Auth(AuthReason::ChannelForbidden));
+    }
 
-    let (jwt, expires_at) = generate_centrifugo_token(user.uuid, channels).await?;
+    let (jwt, expires_at) = generate_centrifugo_subscription_token(user.uuid, body.channel).await?;
 
     let response = GenerateJwtResponse { jwt, expires_at };
     Ok(Json(response).into_response())
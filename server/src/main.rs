@@ -1,8 +1,18 @@
+mod access_tokens;
+mod account;
+mod avatars;
 mod chats;
 mod conn;
+mod contacts;
 mod error;
+mod folders;
 mod jwt;
 mod messages;
+mod moderation;
+mod presence;
+mod privacy;
+mod rate_limit;
+mod signing;
 mod users;
 mod verify_kratos;
 
@@ -11,21 +21,22 @@ mod entities;
 
 pub use conn::db;
 pub use error::AppError;
-pub use jwt::verify_jwt;
-pub use verify_kratos::verify_kratos_cookie;
+pub use jwt::{verify_jwt, AuthUser};
+pub use verify_kratos::{verify_kratos_cookie, KratosSession};
 
 use axum::{
+    extract::DefaultBodyLimit,
     http::{
         header::{AUTHORIZATION, CONTENT_TYPE, COOKIE},
         Method,
     },
     routing::{get, post},
-    Router,
+    Extension, Router,
 };
 use tokio::net::TcpListener;
 use tower_http::cors::CorsLayer;
 
-use utils::config::endpoints;
+use utils::{config::endpoints, data::TokenScope};
 
 #[allow(unused_imports)]
 mod schema {
@@ -37,42 +48,166 @@ mod schema {
 async fn main() {
     tracing_subscriber::fmt::init();
 
+    if signing::key_ring().is_none() {
+        tracing::warn!("No JWT signing key configured. Tokens are signed with shared secrets.");
+    }
+
     let mut app = Router::new();
 
     app = app
         .route(endpoints::jwt::IG_GENERATE, get(jwt::endpoint_generate))
         .route(endpoints::jwt::IG_VERIFY, get(jwt::endpoint_verify))
+        .route(endpoints::jwt::IP_REFRESH, post(jwt::endpoint_refresh))
+        .route(endpoints::jwt::IP_REVOKE, post(jwt::endpoint_revoke))
         .route(
             endpoints::jwt::IG_GENERATE_CENTRIFUGO,
-            get(jwt::endpoint_generate_centrifugo),
+            get(jwt::endpoint_generate_centrifugo).layer(Extension(TokenScope::ReadMessages)),
+        )
+        .route(
+            endpoints::jwt::IP_GENERATE_CENTRIFUGO_SUBSCRIPTION,
+            post(jwt::endpoint_generate_centrifugo_subscription)
+                .layer(Extension(TokenScope::ReadMessages)),
+        )
+        .route(endpoints::jwt::IG_JWKS, get(signing::endpoint_jwks));
+
+    app = app
+        .route(
+            endpoints::access_tokens::IG_LIST,
+            get(access_tokens::list_access_tokens),
+        )
+        .route(
+            endpoints::access_tokens::IP_CREATE,
+            post(access_tokens::create_access_token),
+        )
+        .route(
+            endpoints::access_tokens::IP_REVOKE,
+            post(access_tokens::revoke_access_token),
         );
 
     app = app
-        .route(endpoints::chats::IG_LIST, get(chats::list_chats))
-        .route(endpoints::chats::IP_GET, post(chats::get_chat))
+        .route(endpoints::avatars::IG_GET, get(avatars::get_avatar))
+        .route(
+            endpoints::avatars::IP_UPLOAD,
+            post(avatars::upload_avatar).layer(DefaultBodyLimit::max(avatars::MAX_UPLOAD_BYTES)),
+        )
+        .route(endpoints::avatars::IP_REMOVE, post(avatars::remove_avatar));
+
+    app = app
+        .route(
+            endpoints::contacts::IG_LIST,
+            get(contacts::list_contacts).layer(Extension(TokenScope::ReadMessages)),
+        )
+        .route(endpoints::contacts::IP_SAVE, post(contacts::save_contact))
+        .route(
+            endpoints::contacts::IP_REMOVE,
+            post(contacts::remove_contact),
+        );
+
+    app = app
+        .route(
+            endpoints::chats::IG_LIST,
+            get(chats::list_chats).layer(Extension(TokenScope::ReadMessages)),
+        )
+        .route(
+            endpoints::chats::IP_GET,
+            post(chats::get_chat).layer(Extension(TokenScope::ReadMessages)),
+        )
         .route(
             endpoints::chats::IP_VERIFY_PRIVATE,
-            post(chats::verify_private_chat),
+            post(chats::verify_private_chat).layer(Extension(TokenScope::SendMessages)),
+        )
+        .route(
+            endpoints::chats::IP_PREFERENCES,
+            post(chats::set_chat_preferences),
+        )
+        .route(
+            endpoints::groups::IP_NEW,
+            post(chats::new_group).layer(Extension(TokenScope::ManageGroups)),
         )
-        .route(endpoints::groups::IP_NEW, post(chats::new_group));
+        .route(
+            endpoints::groups::IP_SLOW_MODE,
+            post(chats::set_slow_mode).layer(Extension(TokenScope::ManageGroups)),
+        );
 
     app = app
-        .route(endpoints::messages::IP_LIST, post(messages::list_messages))
-        .route(endpoints::messages::IP_SEND, post(messages::send_message))
+        .route(endpoints::folders::IG_LIST, get(folders::list_folders))
+        .route(endpoints::folders::IP_SAVE, post(folders::save_folder))
+        .route(endpoints::folders::IP_DELETE, post(folders::delete_folder));
+
+    app = app
+        .route(
+            endpoints::messages::IP_LIST,
+            post(messages::list_messages).layer(Extension(TokenScope::ReadMessages)),
+        )
+        .route(
+            endpoints::messages::IP_SEND,
+            post(messages::send_message).layer(Extension(TokenScope::SendMessages)),
+        )
         .route(
             endpoints::messages::IP_DELETE,
-            post(messages::delete_message),
+            post(messages::delete_message).layer(Extension(TokenScope::SendMessages)),
+        )
+        .route(
+            endpoints::messages::IP_EDIT,
+            post(messages::edit_message).layer(Extension(TokenScope::SendMessages)),
+        )
+        .route(
+            endpoints::messages::IP_REPORT,
+            post(moderation::report_message),
+        );
+
+    app = app
+        .route(
+            endpoints::moderation::IG_REPORTS,
+            get(moderation::list_reports),
         )
-        .route(endpoints::messages::IP_EDIT, post(messages::edit_message));
+        .route(
+            endpoints::moderation::IP_RESOLVE,
+            post(moderation::resolve_report),
+        );
+
+    app = app
+        .route(endpoints::presence::IP_HEARTBEAT, post(presence::heartbeat))
+        .route(
+            endpoints::presence::IP_GET,
+            post(presence::get_presence).layer(Extension(TokenScope::ReadMessages)),
+        );
 
     app = app
         .route(endpoints::users::IG_CHECK, get(users::check_user))
-        .route(endpoints::users::IG_ME, get(users::get_me))
-        .route(endpoints::users::IP_GET, post(users::get_user))
-        .route(endpoints::users::IP_NAME, post(users::get_username))
+        .route(
+            endpoints::users::IG_ME,
+            get(users::get_me).layer(Extension(TokenScope::ReadMessages)),
+        )
+        .route(
+            endpoints::users::IP_GET,
+            post(users::get_user).layer(Extension(TokenScope::ReadMessages)),
+        )
+        .route(
+            endpoints::users::IP_NAME,
+            post(users::get_username).layer(Extension(TokenScope::ReadMessages)),
+        )
         .route(endpoints::users::IP_SETUP, post(users::setup_user))
-        .route(endpoints::users::IP_LIST, post(users::list_users))
-        .route(endpoints::users::IP_CHAT, post(users::chat_users));
+        .route(endpoints::users::IP_UPDATE, post(users::update_profile))
+        .route(endpoints::users::IP_STATUS, post(users::set_status))
+        .route(endpoints::users::IG_PRIVACY, get(privacy::get_privacy))
+        .route(
+            endpoints::users::IP_PRIVACY_SAVE,
+            post(privacy::save_privacy),
+        )
+        .route(endpoints::users::IG_EXPORT, get(account::export_data))
+        .route(endpoints::users::IP_DELETE, post(account::delete_account))
+        .route(
+            endpoints::users::IP_SEARCH,
+            post(users::search_users).layer(Extension(TokenScope::ReadMessages)),
+        )
+        .route(
+            endpoints::users::IP_CHAT,
+            post(users::chat_users).layer(Extension(TokenScope::ReadMessages)),
+        )
+        .route(endpoints::users::IG_BLOCKED, get(users::list_blocked_users))
+        .route(endpoints::users::IP_BLOCK, post(users::block_user))
+        .route(endpoints::users::IP_UNBLOCK, post(users::unblock_user));
 
     #[cfg(debug_assertions)]
     {
@@ -98,6 +233,11 @@ async fn main() {
 
     app = app.layer(cors);
 
+    tokio::spawn(presence::sweep_presence());
+    tokio::spawn(rate_limit::sweep_message_rates());
+    tokio::spawn(users::sweep_statuses());
+    tokio::spawn(jwt::sweep_sessions());
+
     let listener = TcpListener::bind("0.0.0.0:3000").await.unwrap();
     axum::serve(listener, app).await.unwrap();
 }
@@ -1,26 +1,36 @@
 use anyhow::{anyhow, Context};
 use axum::{
-    http::HeaderMap,
     response::{IntoResponse, Response},
     Json,
 };
-use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, TransactionTrait,
+};
 
-use crate::{conn::publish, db, schema::*, verify_jwt, AppError};
+use crate::{
+    conn::publish,
+    db,
+    rate_limit::{check_message_rate, record_message_sent},
+    schema::*,
+    users::is_blocked,
+    AppError, AuthUser,
+};
 use utils::{
     data::{MessageInfo, MessageInfoNoReply},
+    errors::{ApiError, ForbiddenReason},
     requests::{
         DeleteMessageRequest, DeleteMessageResponse, EditMessageRequest, ListMessagesRequest,
         ListMessagesResponse, SendMessageRequest, SendMessageResponse,
     },
     updates::{DeleteMessagePayload, Update, UpdateMessagePayload},
+    validation::validate_message,
 };
 
 pub async fn list_messages(
-    headers: HeaderMap,
+    AuthUser(user): AuthUser,
     Json(body): Json<ListMessagesRequest>,
 ) -> Result<Response, AppError> {
-    let user = verify_jwt(&headers).await?;
     let db = db().await;
 
     let _: chat_members::Model = ChatMembers::find()
@@ -73,34 +83,101 @@ pub async fn list_messages(
 }
 
 pub async fn send_message(
-    headers: HeaderMap,
+    AuthUser(user): AuthUser,
     Json(body): Json<SendMessageRequest>,
 ) -> Result<Response, AppError> {
-    let user = verify_jwt(&headers).await?;
+    let content = validate_message(&body.content).map_err(AppError::validation)?;
     let db = db().await;
 
-    let _: chat_members::Model = ChatMembers::find()
+    let (membership, chat): (chat_members::Model, Option<chats::Model>) = ChatMembers::find()
         .filter(chat_members::Column::ChatUuid.eq(body.chat_uuid))
         .filter(chat_members::Column::UserUuid.eq(user.uuid))
+        .find_also_related(Chats)
         .one(db)
         .await
         .context("Failed to query chat membership from database")?
         .ok_or_else(|| anyhow!("User is not a member of this chat"))?;
+    let chat = chat.ok_or_else(|| anyhow!("Chat not found"))?;
+
+    if chat.kind == sea_orm_active_enums::ChatKind::Broadcast
+        && membership.role == sea_orm_active_enums::ChatRole::Member
+    {
+        return Err(anyhow!("Only admins can post in this channel").into());
+    }
+
+    if chat.kind == sea_orm_active_enums::ChatKind::Private {
+        let other_member: Option<chat_members::Model> = ChatMembers::find()
+            .filter(chat_members::Column::ChatUuid.eq(chat.uuid))
+            .filter(chat_members::Column::UserUuid.ne(user.uuid))
+            .one(db)
+            .await
+            .context("Failed to query chat members from database")?;
+
+        if let Some(other_member) = other_member {
+            if is_blocked(user.uuid, other_member.user_uuid).await? {
+                return Err(AppError::forbidden(ForbiddenReason::UserBlocked));
+            }
+        }
+    }
+
+    let txn = db
+        .begin()
+        .await
+        .context("Failed to start database transaction")?;
+
+    if let Some(slow_mode_seconds) = chat.slow_mode_seconds {
+        if membership.role == sea_orm_active_enums::ChatRole::Member {
+            ChatMembers::find()
+                .filter(chat_members::Column::ChatUuid.eq(chat.uuid))
+                .filter(chat_members::Column::UserUuid.eq(user.uuid))
+                .lock_exclusive()
+                .one(&txn)
+                .await
+                .context("Failed to lock chat membership in database")?;
+
+            let last_message: Option<messages::Model> = Messages::find()
+                .filter(messages::Column::ChatUuid.eq(chat.uuid))
+                .filter(messages::Column::SenderUuid.eq(user.uuid))
+                .order_by_desc(messages::Column::CreatedAt)
+                .one(&txn)
+                .await
+                .context("Failed to query last message from database")?;
+
+            if let Some(last_message) = last_message {
+                let elapsed = (chrono::Utc::now().naive_utc() - last_message.created_at)
+                    .num_seconds()
+                    .max(0);
+                let slow_mode_seconds = i64::from(slow_mode_seconds);
+                if elapsed < slow_mode_seconds {
+                    return Err(AppError::Api(ApiError::SlowMode {
+                        retry_after: (slow_mode_seconds - elapsed) as u64,
+                    }));
+                }
+            }
+        }
+    }
+
+    check_message_rate(user.uuid)?;
 
     let new_message = messages::ActiveModel {
         chat_uuid: Set(body.chat_uuid),
         sender_uuid: Set(user.uuid),
-        content: Set(body.content.clone()),
+        content: Set(content),
         reply: Set(body.reply),
         deleted: Set(false),
         ..Default::default()
     };
 
     let inserted_message = new_message
-        .insert(db)
+        .insert(&txn)
         .await
         .context("Failed to insert new message into database")?;
 
+    txn.commit()
+        .await
+        .context("Failed to commit database transaction")?;
+    record_message_sent(user.uuid);
+
     let message_reply = if let Some(reply_uuid) = body.reply {
         let reply_message: messages::Model = Messages::find()
             .filter(messages::Column::Uuid.eq(reply_uuid))
@@ -135,10 +212,9 @@ pub async fn send_message(
 }
 
 pub async fn delete_message(
-    headers: HeaderMap,
+    AuthUser(user): AuthUser,
     Json(body): Json<DeleteMessageRequest>,
 ) -> Result<Response, AppError> {
-    let user = verify_jwt(&headers).await?;
     let db = db().await;
 
     let message: messages::Model = Messages::find()
@@ -170,10 +246,9 @@ pub async fn delete_message(
 }
 
 pub async fn edit_message(
-    headers: HeaderMap,
+    AuthUser(user): AuthUser,
     Json(body): Json<EditMessageRequest>,
 ) -> Result<Response, AppError> {
-    let user = verify_jwt(&headers).await?;
     let db = db().await;
 
     let message: messages::Model = Messages::find()
@@ -187,8 +262,10 @@ pub async fn edit_message(
         return Err(anyhow!("User is not the sender of this message").into());
     }
 
+    let new_content = validate_message(&body.new_content).map_err(AppError::validation)?;
+
     let mut message_active: messages::ActiveModel = message.into();
-    message_active.content = Set(body.new_content.clone());
+    message_active.content = Set(new_content);
     message_active.edited_at = Set(Some(chrono::Utc::now().naive_utc()));
     let message = message_active
         .update(db)
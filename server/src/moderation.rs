@@ -0,0 +1,298 @@
+use anyhow::{anyhow, Context};
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, ModelTrait, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+
+use crate::{
+    chats::transfer_ownership,
+    conn::{disconnect, publish, unsubscribe},
+    db,
+    jwt::revoke_sessions,
+    schema::*,
+    AppError, AuthUser,
+};
+use utils::{
+    data::{self, MessageInfoNoReply, ReportInfo},
+    errors::ForbiddenReason,
+    requests::{
+        ListReportsResponse, ReportMessageRequest, ReportMessageResponse, ResolveReportRequest,
+        ResolveReportResponse,
+    },
+    updates::{DeleteMessagePayload, ReportResolvedPayload, Update},
+    validation::validate_report_reason,
+};
+
+impl From<data::ModerationAction> for sea_orm_active_enums::ModerationAction {
+    fn from(action: data::ModerationAction) -> Self {
+        match action {
+            data::ModerationAction::Dismiss => sea_orm_active_enums::ModerationAction::Dismiss,
+            data::ModerationAction::DeleteMessage => {
+                sea_orm_active_enums::ModerationAction::DeleteMessage
+            }
+            data::ModerationAction::RemoveFromGroup => {
+                sea_orm_active_enums::ModerationAction::RemoveFromGroup
+            }
+            data::ModerationAction::BanUser => sea_orm_active_enums::ModerationAction::BanUser,
+        }
+    }
+}
+
+fn verify_moderator(user: &users::Model) -> Result<(), AppError> {
+    if !user.is_moderator {
+        return Err(AppError::forbidden(ForbiddenReason::NotModerator));
+    }
+
+    Ok(())
+}
+
+pub async fn report_message(
+    AuthUser(user): AuthUser,
+    Json(body): Json<ReportMessageRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let reason = validate_report_reason(&body.reason).map_err(AppError::validation)?;
+
+    let message: messages::Model = Messages::find()
+        .filter(messages::Column::Uuid.eq(body.message_uuid))
+        .filter(messages::Column::Deleted.eq(false))
+        .one(db)
+        .await
+        .context("Failed to query message from database")?
+        .ok_or_else(|| anyhow!("Message not found"))?;
+
+    let _: chat_members::Model = ChatMembers::find()
+        .filter(chat_members::Column::ChatUuid.eq(message.chat_uuid))
+        .filter(chat_members::Column::UserUuid.eq(user.uuid))
+        .one(db)
+        .await
+        .context("Failed to query chat membership from database")?
+        .ok_or_else(|| anyhow!("User is not a member of this chat"))?;
+
+    if message.sender_uuid == user.uuid {
+        return Err(anyhow!("Cannot report your own message").into());
+    }
+
+    let new_report = reports::ActiveModel {
+        message_uuid: Set(message.uuid),
+        reporter_uuid: Set(user.uuid),
+        reason: Set(reason),
+        ..Default::default()
+    };
+    new_report
+        .insert(db)
+        .await
+        .context("Failed to insert report into database")?;
+
+    let response = ReportMessageResponse {};
+    Ok(Json(response).into_response())
+}
+
+pub async fn list_reports(AuthUser(user): AuthUser) -> Result<Response, AppError> {
+    verify_moderator(&user)?;
+    let db = db().await;
+
+    let report_models: Vec<(reports::Model, Option<messages::Model>)> = Reports::find()
+        .filter(reports::Column::ResolvedAt.is_null())
+        .order_by_asc(reports::Column::CreatedAt)
+        .find_also_related(Messages)
+        .all(db)
+        .await
+        .context("Failed to query reports from database")?;
+
+    let reports: Vec<ReportInfo> = report_models
+        .into_iter()
+        .filter_map(|(report, message)| {
+            message.map(|message| ReportInfo {
+                uuid: report.uuid,
+                chat_uuid: message.chat_uuid,
+                reporter_uuid: report.reporter_uuid,
+                message: MessageInfoNoReply {
+                    uuid: message.uuid,
+                    sender_uuid: message.sender_uuid,
+                    content: message.content,
+                    created_at: message.created_at,
+                    edited_at: message.edited_at,
+                },
+                reason: report.reason,
+                created_at: report.created_at,
+            })
+        })
+        .collect();
+
+    let response = ListReportsResponse(reports);
+    Ok(Json(response).into_response())
+}
+
+pub async fn resolve_report(
+    AuthUser(user): AuthUser,
+    Json(body): Json<ResolveReportRequest>,
+) -> Result<Response, AppError> {
+    verify_moderator(&user)?;
+    let db = db().await;
+
+    let report: reports::Model = Reports::find()
+        .filter(reports::Column::Uuid.eq(body.report_uuid))
+        .one(db)
+        .await
+        .context("Failed to query report from database")?
+        .ok_or_else(|| anyhow!("Report not found"))?;
+
+    if report.resolved_at.is_some() {
+        return Err(anyhow!("Report is already resolved").into());
+    }
+
+    let message: messages::Model = report
+        .find_related(Messages)
+        .one(db)
+        .await
+        .context("Failed to query reported message from database")?
+        .ok_or_else(|| anyhow!("Reported message not found"))?;
+
+    let chat: chats::Model = Chats::find_by_id(message.chat_uuid)
+        .one(db)
+        .await
+        .context("Failed to query chat from database")?
+        .ok_or_else(|| anyhow!("Chat not found"))?;
+
+    let txn = db
+        .begin()
+        .await
+        .context("Failed to start database transaction")?;
+
+    match body.action {
+        data::ModerationAction::Dismiss => {}
+        data::ModerationAction::DeleteMessage => {
+            let mut message_active: messages::ActiveModel = message.clone().into();
+            message_active.deleted = Set(true);
+            message_active
+                .update(&txn)
+                .await
+                .context("Failed to delete message in database")?;
+        }
+        data::ModerationAction::RemoveFromGroup => {
+            if chat.kind == sea_orm_active_enums::ChatKind::Private {
+                return Err(anyhow!("Cannot remove a user from a private chat").into());
+            }
+
+            let membership: Option<chat_members::Model> = ChatMembers::find()
+                .filter(chat_members::Column::ChatUuid.eq(chat.uuid))
+                .filter(chat_members::Column::UserUuid.eq(message.sender_uuid))
+                .one(&txn)
+                .await
+                .context("Failed to query chat membership from database")?;
+            if membership
+                .is_some_and(|membership| membership.role == sea_orm_active_enums::ChatRole::Owner)
+            {
+                transfer_ownership(&txn, chat.uuid, message.sender_uuid).await?;
+            }
+
+            ChatMembers::delete_many()
+                .filter(chat_members::Column::ChatUuid.eq(chat.uuid))
+                .filter(chat_members::Column::UserUuid.eq(message.sender_uuid))
+                .exec(&txn)
+                .await
+                .context("Failed to remove user from chat in database")?;
+        }
+        data::ModerationAction::BanUser => {
+            let sender: users::Model = Users::find_by_id(message.sender_uuid)
+                .one(&txn)
+                .await
+                .context("Failed to query user from database")?
+                .ok_or_else(|| anyhow!("User not found"))?;
+
+            let mut sender_active: users::ActiveModel = sender.into();
+            sender_active.banned_at = Set(Some(chrono::Utc::now().naive_utc()));
+            sender_active
+                .update(&txn)
+                .await
+                .context("Failed to ban user in database")?;
+
+            revoke_sessions(&txn, message.sender_uuid).await?;
+        }
+    }
+
+    let moderation_action = moderation_actions::ActiveModel {
+        moderator_uuid: Set(user.uuid),
+        report_uuid: Set(Some(report.uuid)),
+        action: Set(body.action.into()),
+        target_user_uuid: Set(message.sender_uuid),
+        target_message_uuid: Set(Some(message.uuid)),
+        chat_uuid: Set(Some(chat.uuid)),
+        ..Default::default()
+    };
+    moderation_action
+        .insert(&txn)
+        .await
+        .context("Failed to record moderation action in database")?;
+
+    let resolved_reports: Vec<reports::Model> = Reports::find()
+        .filter(reports::Column::MessageUuid.eq(message.uuid))
+        .filter(reports::Column::ResolvedAt.is_null())
+        .all(&txn)
+        .await
+        .context("Failed to query open reports from database")?;
+
+    let resolved_at = chrono::Utc::now().naive_utc();
+    for resolved_report in resolved_reports.iter().cloned() {
+        let mut report_active: reports::ActiveModel = resolved_report.into();
+        report_active.resolved_at = Set(Some(resolved_at));
+        report_active.resolved_by = Set(Some(user.uuid));
+        report_active.action = Set(Some(body.action.into()));
+        report_active
+            .update(&txn)
+            .await
+            .context("Failed to resolve report in database")?;
+    }
+
+    txn.commit()
+        .await
+        .context("Failed to commit database transaction")?;
+
+    match body.action {
+        data::ModerationAction::DeleteMessage => {
+            let update = Update::DeleteMessage(DeleteMessagePayload {
+                chat_uuid: chat.uuid,
+                message_uuid: message.uuid,
+            });
+            if let Err(e) = publish(&format!("chat_{}", chat.uuid), update).await {
+                tracing::error!("Failed to publish deleted message: {:?}", e);
+            }
+        }
+        data::ModerationAction::RemoveFromGroup => {
+            let update = Update::ChatRemoved(chat.uuid);
+            if let Err(e) = publish(&format!("user_{}", message.sender_uuid), update).await {
+                tracing::error!("Failed to publish chat removal: {:?}", e);
+            }
+            let channel = format!("chat_{}", chat.uuid);
+            if let Err(e) = unsubscribe(message.sender_uuid, &channel).await {
+                tracing::error!("Failed to unsubscribe removed user: {:?}", e);
+            }
+        }
+        data::ModerationAction::BanUser => {
+            if let Err(e) = disconnect(message.sender_uuid).await {
+                tracing::error!("Failed to disconnect banned user: {:?}", e);
+            }
+        }
+        data::ModerationAction::Dismiss => {}
+    }
+
+    for resolved_report in resolved_reports {
+        let update = Update::ReportResolved(ReportResolvedPayload {
+            report_uuid: resolved_report.uuid,
+            action: body.action,
+        });
+        let channel = format!("user_{}", resolved_report.reporter_uuid);
+        if let Err(e) = publish(&channel, update).await {
+            tracing::error!("Failed to publish resolved report: {:?}", e);
+        }
+    }
+
+    let response = ResolveReportResponse {};
+    Ok(Json(response).into_response())
+}
@@ -0,0 +1,205 @@
+use anyhow::Context;
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use sea_orm::{
+    prelude::Uuid, ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter,
+};
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    conn::publish,
+    db,
+    privacy::{audience_allows, shares_group},
+    schema::*,
+    AppError, AuthUser,
+};
+use utils::{
+    data::Presence,
+    requests::{GetPresenceRequest, GetPresenceResponse, HeartbeatResponse},
+    updates::Update,
+};
+
+const ONLINE_WINDOW: Duration = Duration::from_secs(60);
+const SWEEP_INTERVAL: Duration = Duration::from_secs(15);
+
+static LAST_HEARTBEATS: LazyLock<Mutex<HashMap<Uuid, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn is_online(user_uuid: Uuid) -> bool {
+    LAST_HEARTBEATS
+        .lock()
+        .unwrap()
+        .get(&user_uuid)
+        .is_some_and(|seen| seen.elapsed() < ONLINE_WINDOW)
+}
+
+pub fn presence(user: &users::Model) -> Presence {
+    Presence {
+        user_uuid: user.uuid,
+        online: is_online(user.uuid),
+        last_seen_at: user.last_seen_at,
+    }
+}
+
+fn hidden_presence(user_uuid: Uuid) -> Presence {
+    Presence {
+        user_uuid,
+        online: false,
+        last_seen_at: None,
+    }
+}
+
+pub async fn visible_presence(
+    user: &users::Model,
+    viewer_uuid: Uuid,
+) -> Result<Presence, AppError> {
+    if audience_allows(&user.last_seen_privacy, user.uuid, viewer_uuid).await? {
+        Ok(presence(user))
+    } else {
+        Ok(hidden_presence(user.uuid))
+    }
+}
+
+pub async fn publish_presence(user: &users::Model) -> Result<(), AppError> {
+    let memberships: Vec<(chat_members::Model, Option<chats::Model>)> = ChatMembers::find()
+        .filter(chat_members::Column::UserUuid.eq(user.uuid))
+        .find_also_related(Chats)
+        .all(db().await)
+        .await
+        .context("Failed to query chat memberships from database")?;
+
+    for (chat_member, chat) in memberships {
+        let visible = match (&user.last_seen_privacy, chat) {
+            (sea_orm_active_enums::PrivacyAudience::Everyone, _) => true,
+            (sea_orm_active_enums::PrivacyAudience::Nobody, _) => false,
+            (sea_orm_active_enums::PrivacyAudience::SharedGroup, Some(chat))
+                if chat.kind == sea_orm_active_enums::ChatKind::Private =>
+            {
+                let counterpart = [chat.private_first_uuid, chat.private_second_uuid]
+                    .into_iter()
+                    .flatten()
+                    .find(|uuid| *uuid != user.uuid);
+                match counterpart {
+                    Some(counterpart) => shares_group(user.uuid, counterpart).await?,
+                    None => false,
+                }
+            }
+            (sea_orm_active_enums::PrivacyAudience::SharedGroup, _) => true,
+        };
+
+        let presence = if visible {
+            presence(user)
+        } else {
+            hidden_presence(user.uuid)
+        };
+        publish(
+            &format!("chat_{}", chat_member.chat_uuid),
+            Update::PresenceChanged(presence),
+        )
+        .await?;
+    }
+
+    let watchers: Vec<contacts::Model> = Contacts::find()
+        .filter(contacts::Column::ContactUuid.eq(user.uuid))
+        .all(db().await)
+        .await
+        .context("Failed to query contacts from database")?;
+
+    for watcher in watchers {
+        let presence = visible_presence(user, watcher.owner_uuid).await?;
+        publish(
+            &format!("user_{}", watcher.owner_uuid),
+            Update::PresenceChanged(presence),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn heartbeat(AuthUser(user): AuthUser) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let now = Utc::now().naive_utc();
+    let mut user_active: users::ActiveModel = user.into();
+    user_active.last_seen_at = Set(Some(now));
+    let user = user_active
+        .update(db)
+        .await
+        .context("Failed to update last seen time in database")?;
+
+    let came_online = LAST_HEARTBEATS
+        .lock()
+        .unwrap()
+        .insert(user.uuid, Instant::now())
+        .is_none_or(|seen| seen.elapsed() >= ONLINE_WINDOW);
+    if came_online {
+        publish_presence(&user).await?;
+    }
+
+    let response = HeartbeatResponse {};
+    Ok(Json(response).into_response())
+}
+
+pub async fn get_presence(
+    AuthUser(user): AuthUser,
+    Json(body): Json<GetPresenceRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let user_models: Vec<users::Model> = Users::find()
+        .filter(users::Column::Uuid.is_in(body.0))
+        .all(db)
+        .await
+        .context("Failed to query users from database")?;
+
+    let mut presences: Vec<Presence> = Vec::with_capacity(user_models.len());
+    for user_model in &user_models {
+        presences.push(visible_presence(user_model, user.uuid).await?);
+    }
+
+    let response = GetPresenceResponse(presences);
+    Ok(Json(response).into_response())
+}
+
+pub async fn sweep_presence() {
+    loop {
+        tokio::time::sleep(SWEEP_INTERVAL).await;
+
+        let expired: Vec<Uuid> = {
+            let mut heartbeats = LAST_HEARTBEATS.lock().unwrap();
+            let expired = heartbeats
+                .iter()
+                .filter(|(_, seen)| seen.elapsed() >= ONLINE_WINDOW)
+                .map(|(user_uuid, _)| *user_uuid)
+                .collect::<Vec<_>>();
+            for user_uuid in &expired {
+                heartbeats.remove(user_uuid);
+            }
+            expired
+        };
+
+        for user_uuid in expired {
+            let user = Users::find_by_id(user_uuid)
+                .one(db().await)
+                .await
+                .context("Failed to query user from database");
+
+            let result = match user {
+                Ok(Some(user)) => publish_presence(&user).await,
+                Ok(None) => Ok(()),
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = result {
+                tracing::error!("Failed to publish presence: {:?}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use anyhow::Context;
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use sea_orm::{
+    prelude::Uuid, sea_query::Query, ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait,
+    JoinType, PaginatorTrait, QueryFilter, QuerySelect, RelationTrait,
+};
+
+use crate::{db, presence::publish_presence, schema::*, AppError, AuthUser};
+use utils::{
+    data::{self, PrivacySettings},
+    requests::{GetPrivacyResponse, SavePrivacyRequest, SavePrivacyResponse},
+};
+
+impl From<sea_orm_active_enums::PrivacyAudience> for data::PrivacyAudience {
+    fn from(audience: sea_orm_active_enums::PrivacyAudience) -> Self {
+        match audience {
+            sea_orm_active_enums::PrivacyAudience::Everyone => data::PrivacyAudience::Everyone,
+            sea_orm_active_enums::PrivacyAudience::SharedGroup => {
+                data::PrivacyAudience::SharedGroup
+            }
+            sea_orm_active_enums::PrivacyAudience::Nobody => data::PrivacyAudience::Nobody,
+        }
+    }
+}
+
+impl From<data::PrivacyAudience> for sea_orm_active_enums::PrivacyAudience {
+    fn from(audience: data::PrivacyAudience) -> Self {
+        match audience {
+            data::PrivacyAudience::Everyone => sea_orm_active_enums::PrivacyAudience::Everyone,
+            data::PrivacyAudience::SharedGroup => {
+                sea_orm_active_enums::PrivacyAudience::SharedGroup
+            }
+            data::PrivacyAudience::Nobody => sea_orm_active_enums::PrivacyAudience::Nobody,
+        }
+    }
+}
+
+pub fn privacy_settings(user: &users::Model) -> PrivacySettings {
+    PrivacySettings {
+        dm: user.dm_privacy.clone().into(),
+        last_seen: user.last_seen_privacy.clone().into(),
+        discoverable: user.discoverable,
+    }
+}
+
+pub async fn shares_group(first_uuid: Uuid, second_uuid: Uuid) -> Result<bool, AppError> {
+    let first_chats = Query::select()
+        .column(chat_members::Column::ChatUuid)
+        .from(ChatMembers)
+        .and_where(chat_members::Column::UserUuid.eq(first_uuid))
+        .to_owned();
+
+    let shared = ChatMembers::find()
+        .join(JoinType::InnerJoin, chat_members::Relation::Chats.def())
+        .filter(chat_members::Column::UserUuid.eq(second_uuid))
+        .filter(chat_members::Column::ChatUuid.in_subquery(first_chats))
+        .filter(chats::Column::Kind.ne(sea_orm_active_enums::ChatKind::Private))
+        .count(db().await)
+        .await
+        .context("Failed to query shared groups from database")?;
+
+    Ok(shared > 0)
+}
+
+pub async fn audience_allows(
+    audience: &sea_orm_active_enums::PrivacyAudience,
+    owner_uuid: Uuid,
+    viewer_uuid: Uuid,
+) -> Result<bool, AppError> {
+    if owner_uuid == viewer_uuid {
+        return Ok(true);
+    }
+
+    match audience {
+        sea_orm_active_enums::PrivacyAudience::Everyone => Ok(true),
+        sea_orm_active_enums::PrivacyAudience::SharedGroup => {
+            shares_group(owner_uuid, viewer_uuid).await
+        }
+        sea_orm_active_enums::PrivacyAudience::Nobody => Ok(false),
+    }
+}
+
+pub async fn get_privacy(AuthUser(user): AuthUser) -> Result<Response, AppError> {
+    let response = GetPrivacyResponse(privacy_settings(&user));
+    Ok(Json(response).into_response())
+}
+
+pub async fn save_privacy(
+    AuthUser(user): AuthUser,
+    Json(body): Json<SavePrivacyRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let settings = body.0;
+    let last_seen_changed =
+        data::PrivacyAudience::from(user.last_seen_privacy.clone()) != settings.last_seen;
+
+    let mut user_active: users::ActiveModel = user.into();
+    user_active.dm_privacy = Set(settings.dm.into());
+    user_active.last_seen_privacy = Set(settings.last_seen.into());
+    user_active.discoverable = Set(settings.discoverable);
+    let user = user_active
+        .update(db)
+        .await
+        .context("Failed to update privacy settings in database")?;
+
+    if last_seen_changed {
+        publish_presence(&user).await?;
+    }
+
+    let response = SavePrivacyResponse(privacy_settings(&user));
+    Ok(Json(response).into_response())
+}
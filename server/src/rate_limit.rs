@@ -0,0 +1,60 @@
+use sea_orm::prelude::Uuid;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::AppError;
+use utils::errors::ApiError;
+
+const MESSAGE_LIMIT: usize = 20;
+const MESSAGE_WINDOW: Duration = Duration::from_secs(10);
+
+static MESSAGE_SENDS: LazyLock<Mutex<HashMap<Uuid, VecDeque<Instant>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub fn check_message_rate(user_uuid: Uuid) -> Result<(), AppError> {
+    let now = Instant::now();
+    let mut sends = MESSAGE_SENDS.lock().unwrap();
+
+    let user_sends = sends.entry(user_uuid).or_default();
+    while user_sends
+        .front()
+        .is_some_and(|sent_at| now.duration_since(*sent_at) >= MESSAGE_WINDOW)
+    {
+        user_sends.pop_front();
+    }
+
+    if user_sends.len() >= MESSAGE_LIMIT {
+        let oldest = user_sends.front().copied().unwrap_or(now);
+        let retry_after = MESSAGE_WINDOW.saturating_sub(now.duration_since(oldest));
+        return Err(AppError::Api(ApiError::RateLimited {
+            retry_after: retry_after.as_secs().max(1),
+        }));
+    }
+
+    Ok(())
+}
+
+pub fn record_message_sent(user_uuid: Uuid) {
+    MESSAGE_SENDS
+        .lock()
+        .unwrap()
+        .entry(user_uuid)
+        .or_default()
+        .push_back(Instant::now());
+}
+
+pub async fn sweep_message_rates() {
+    loop {
+        tokio::time::sleep(MESSAGE_WINDOW).await;
+
+        let now = Instant::now();
+        MESSAGE_SENDS.lock().unwrap().retain(|_, user_sends| {
+            user_sends
+                .back()
+                .is_some_and(|sent_at| now.duration_since(*sent_at) < MESSAGE_WINDOW)
+        });
+    }
+}
@@ -0,0 +1,185 @@
+use anyhow::{anyhow, bail, Context, Result};
+use aws_lc_rs::signature::{Ed25519KeyPair, KeyPair};
+use axum::{
+    http::header::CACHE_CONTROL,
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse,
+    },
+    Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::HashMap, sync::OnceLock};
+
+use crate::AppError;
+use utils::config::server::{
+    jwt_signing_algorithm, jwt_signing_key_file, jwt_signing_key_id, jwt_verification_keys_file,
+};
+
+static KEY_RING: OnceLock<Option<KeyRing>> = OnceLock::new();
+
+struct SigningKey {
+    kid: String,
+    algorithm: Algorithm,
+    key: EncodingKey,
+}
+
+pub struct KeyRing {
+    signing: SigningKey,
+    verification: HashMap<String, (Algorithm, DecodingKey)>,
+    jwks: JwkSet,
+}
+
+fn public_jwk(algorithm: Algorithm, pem: &[u8], key: &EncodingKey) -> Result<Jwk> {
+    match algorithm {
+        Algorithm::EdDSA => {
+            let der = pem::parse(pem).context("Failed to parse signing key PEM")?;
+            let pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(der.contents())
+                .map_err(|e| anyhow!("Failed to parse Ed25519 signing key: {}", e))?;
+            Ok(Jwk {
+                common: CommonParameters {
+                    key_algorithm: Some(KeyAlgorithm::EdDSA),
+                    ..Default::default()
+                },
+                algorithm: AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                    key_type: OctetKeyPairType::OctetKeyPair,
+                    curve: EllipticCurve::Ed25519,
+                    x: URL_SAFE_NO_PAD.encode(pair.public_key().as_ref()),
+                }),
+            })
+        }
+        Algorithm::RS256 => {
+            Jwk::from_encoding_key(key, algorithm).context("Failed to derive RSA public key")
+        }
+        _ => bail!("Unsupported JWT signing algorithm: {:?}", algorithm),
+    }
+}
+
+fn jwk_algorithm(jwk: &Jwk) -> Result<Algorithm> {
+    match jwk.common.key_algorithm {
+        Some(KeyAlgorithm::EdDSA) => Ok(Algorithm::EdDSA),
+        Some(KeyAlgorithm::RS256) => Ok(Algorithm::RS256),
+        other => bail!("Unsupported verification key algorithm: {:?}", other),
+    }
+}
+
+fn load_key_ring() -> Result<Option<KeyRing>> {
+    let Some(path) = jwt_signing_key_file() else {
+        return Ok(None);
+    };
+
+    let kid = jwt_signing_key_id();
+    let algorithm: Algorithm = jwt_signing_algorithm()
+        .parse()
+        .context("Invalid JWT signing algorithm")?;
+
+    let pem = std::fs::read(&path).context("Failed to read JWT signing key")?;
+    let key = match algorithm {
+        Algorithm::EdDSA => EncodingKey::from_ed_pem(&pem),
+        Algorithm::RS256 => EncodingKey::from_rsa_pem(&pem),
+        _ => bail!("Unsupported JWT signing algorithm: {:?}", algorithm),
+    }
+    .context("Failed to load JWT signing key")?;
+
+    let mut signing_jwk = public_jwk(algorithm, &pem, &key)?;
+    signing_jwk.common.key_id = Some(kid.clone());
+
+    let mut jwks = vec![signing_jwk];
+    if let Some(path) = jwt_verification_keys_file() {
+        let content = std::fs::read(&path).context("Failed to read JWT verification keys")?;
+        let retired: JwkSet =
+            serde_json::from_slice(&content).context("Failed to parse JWT verification keys")?;
+        jwks.extend(retired.keys);
+    }
+
+    let mut verification = HashMap::new();
+    for jwk in jwks.iter_mut() {
+        let jwk_kid = jwk
+            .common
+            .key_id
+            .clone()
+            .ok_or_else(|| anyhow!("Verification key is missing a kid"))?;
+        let jwk_algorithm = jwk_algorithm(jwk)?;
+        let decoding = DecodingKey::from_jwk(jwk).context("Failed to load verification key")?;
+        if verification
+            .insert(jwk_kid.clone(), (jwk_algorithm, decoding))
+            .is_some()
+        {
+            bail!("Duplicate verification key id: {}", jwk_kid);
+        }
+        jwk.common.public_key_use = Some(PublicKeyUse::Signature);
+    }
+
+    Ok(Some(KeyRing {
+        signing: SigningKey {
+            kid,
+            algorithm,
+            key,
+        },
+        verification,
+        jwks: JwkSet { keys: jwks },
+    }))
+}
+
+pub fn key_ring() -> Option<&'static KeyRing> {
+    KEY_RING
+        .get_or_init(|| load_key_ring().expect("Failed to load JWT signing keys"))
+        .as_ref()
+}
+
+pub fn encode<T: Serialize>(claims: &T, secret: fn() -> Vec<u8>) -> Result<String> {
+    let token = match key_ring() {
+        Some(ring) => {
+            let mut header = Header::new(ring.signing.algorithm);
+            header.kid = Some(ring.signing.kid.clone());
+            jsonwebtoken::encode(&header, claims, &ring.signing.key)
+        }
+        None => jsonwebtoken::encode(
+            &Header::default(),
+            claims,
+            &EncodingKey::from_secret(&secret()),
+        ),
+    }?;
+
+    Ok(token)
+}
+
+pub fn decode<T: DeserializeOwned + Clone>(
+    token: &str,
+    audience: &str,
+    secret: fn() -> Vec<u8>,
+) -> Result<T> {
+    let (algorithm, key) = match key_ring() {
+        Some(ring) => {
+            let kid = jsonwebtoken::decode_header(token)?
+                .kid
+                .ok_or_else(|| anyhow!("JWT is missing a kid"))?;
+            let (algorithm, key) = ring
+                .verification
+                .get(&kid)
+                .ok_or_else(|| anyhow!("Unknown JWT kid: {}", kid))?;
+            (*algorithm, key.clone())
+        }
+        None => (Algorithm::HS256, DecodingKey::from_secret(&secret())),
+    };
+
+    let mut validation = Validation::new(algorithm);
+    validation.set_audience(&[audience]);
+    let data = jsonwebtoken::decode::<T>(token, &key, &validation)?;
+
+    Ok(data.claims)
+}
+
+pub async fn endpoint_jwks() -> Result<Response, AppError> {
+    let jwks = match key_ring() {
+        Some(ring) => ring.jwks.clone(),
+        None => JwkSet { keys: Vec::new() },
+    };
+
+    Ok(([(CACHE_CONTROL, "public, max-age=300")], Json(jwks)).into_response())
+}
@@ -1,29 +1,89 @@
 use anyhow::{anyhow, Context};
 use axum::{
-    http::HeaderMap,
     response::{IntoResponse, Response},
     Json,
 };
+use chrono::{Duration, Utc};
 use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    prelude::{Expr, Uuid},
+    sea_query::{Func, OnConflict, Query, SimpleExpr},
+    ActiveModelTrait,
+    ActiveValue::Set,
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, JoinType, Order, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait, SqlErr, TransactionTrait,
 };
 
-use crate::{db, schema::*, verify_jwt, verify_kratos_cookie, AppError};
+use crate::{conn::publish, db, schema::*, AppError, AuthUser, KratosSession};
 use utils::{
-    data::UserInfo,
+    data::{UserInfo, UserStatus},
     requests::{
-        ChatUsersRequest, CheckUserResponse, GetUserRequest, GetUserResponse, GetUsernameRequest,
-        ListUsersRequest, ListUsersResponse, SetupUserRequest, SetupUserResponse,
+        BlockUserRequest, BlockUserResponse, ChatUsersRequest, CheckUserResponse, GetUserRequest,
+        GetUserResponse, GetUsernameRequest, ListUsersResponse, SearchUsersRequest,
+        SearchUsersResponse, SetStatusRequest, SetStatusResponse, SetupUserRequest,
+        SetupUserResponse, UnblockUserRequest, UnblockUserResponse, UpdateProfileRequest,
+        UpdateProfileResponse,
+    },
+    updates::Update,
+    validation::{
+        username_key, validate_bio, validate_nickname, validate_status, validate_username, Field,
+        Reason, ValidationError,
     },
 };
 
+const USERNAME_REDIRECT_DAYS: i64 = 30;
+const USERNAME_UNIQUE_INDEX: &str = "idx-users-username-lower";
+const SEARCH_LIMIT_MAX: u64 = 50;
+const STATUS_SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+pub fn user_info(user: users::Model) -> UserInfo {
+    UserInfo {
+        uuid: user.uuid,
+        username: user.username,
+        nickname: user.nickname,
+        avatar_version: user
+            .avatar_updated_at
+            .map(|updated_at| updated_at.and_utc().timestamp_millis()),
+        deleted: user.deleted_at.is_some(),
+        bio: user.bio,
+        status: match (user.status_emoji, user.status_text) {
+            (Some(emoji), Some(text)) => Some(UserStatus {
+                emoji,
+                text,
+                expires_at: user.status_expires_at,
+            }),
+            _ => None,
+        }
+        .filter(|status| !status.is_expired(Utc::now().naive_utc())),
+    }
+}
+
+pub async fn publish_user_updated(user: &UserInfo) -> Result<(), AppError> {
+    let chat_members: Vec<chat_members::Model> = ChatMembers::find()
+        .filter(chat_members::Column::UserUuid.eq(user.uuid))
+        .all(db().await)
+        .await
+        .context("Failed to query chat memberships from database")?;
+
+    let channels = chat_members
+        .into_iter()
+        .map(|chat_member| format!("chat_{}", chat_member.chat_uuid))
+        .chain([format!("user_{}", user.uuid)]);
+    for channel in channels {
+        if let Err(e) = publish(&channel, Update::UserUpdated(user.clone())).await {
+            tracing::error!("Failed to publish user update to {}: {:?}", channel, e);
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(debug_assertions)]
 pub async fn debug_user(Json(body): Json<UserInfo>) -> Result<Response, AppError> {
     let db = db().await;
 
     let user_model = users::ActiveModel {
+        email_hash: Set(format!("{:x}", md5::compute(&body.username))),
         username: Set(body.username),
-        email_hash: Set(body.email_hash),
         nickname: Set(body.nickname),
         ..Default::default()
     };
@@ -32,16 +92,14 @@ pub async fn debug_user(Json(body): Json<UserInfo>) -> Result<Response, AppError
         .await
         .context("Failed to insert new user into database")?;
 
-    let (jwt, expires_at) = crate::jwt::generate(user.uuid)
+    let response = crate::jwt::create_session(user.uuid)
         .await
-        .context("Failed to generate JWT")?;
-
-    let response = utils::requests::GenerateJwtResponse { jwt, expires_at };
+        .context("Failed to create session")?;
     Ok(Json(response).into_response())
 }
 
-pub async fn check_user(headers: HeaderMap) -> Result<Response, AppError> {
-    let email = verify_kratos_cookie(&headers).await?.identity.traits.email;
+pub async fn check_user(KratosSession(session): KratosSession) -> Result<Response, AppError> {
+    let email = session.identity.traits.email;
     let db = db().await;
 
     let user_model = Users::find()
@@ -54,25 +112,14 @@ pub async fn check_user(headers: HeaderMap) -> Result<Response, AppError> {
     Ok(Json(response).into_response())
 }
 
-pub async fn get_me(headers: HeaderMap) -> Result<Response, AppError> {
-    let user_model = verify_jwt(&headers).await?;
-
-    let user = UserInfo {
-        uuid: user_model.uuid,
-        email_hash: user_model.email_hash,
-        username: user_model.username,
-        nickname: user_model.nickname,
-    };
+pub async fn get_me(AuthUser(user_model): AuthUser) -> Result<Response, AppError> {
+    let user = user_info(user_model);
 
     let response = GetUserResponse(user);
     Ok(Json(response).into_response())
 }
 
-pub async fn get_user(
-    headers: HeaderMap,
-    Json(body): Json<GetUserRequest>,
-) -> Result<Response, AppError> {
-    let _ = verify_jwt(&headers).await?;
+pub async fn get_user(_: AuthUser, Json(body): Json<GetUserRequest>) -> Result<Response, AppError> {
     let db = db().await;
 
     let user_model: users::Model = Users::find()
@@ -82,62 +129,110 @@ pub async fn get_user(
         .context("Failed to query user from database")?
         .ok_or_else(|| anyhow!("User not found"))?;
 
-    let user = UserInfo {
-        uuid: user_model.uuid,
-        email_hash: user_model.email_hash,
-        username: user_model.username,
-        nickname: user_model.nickname,
-    };
+    let user = user_info(user_model);
 
     let response = GetUserResponse(user);
     Ok(Json(response).into_response())
 }
 
 pub async fn get_username(
-    headers: HeaderMap,
+    _: AuthUser,
     Json(body): Json<GetUsernameRequest>,
 ) -> Result<Response, AppError> {
-    let _ = verify_jwt(&headers).await?;
     let db = db().await;
 
-    let user_model: users::Model = Users::find()
-        .filter(users::Column::Username.eq(body.0))
+    let user_model: Option<users::Model> = Users::find()
+        .filter(username_matches(users::Column::Username, &body.0))
         .one(db)
         .await
-        .context("Failed to query user from database")?
-        .ok_or_else(|| anyhow!("User not found"))?;
+        .context("Failed to query user from database")?;
 
-    let user = UserInfo {
-        uuid: user_model.uuid,
-        email_hash: user_model.email_hash,
-        username: user_model.username,
-        nickname: user_model.nickname,
+    let user_model: users::Model = match user_model {
+        Some(user_model) => user_model,
+        None => UsernameRedirects::find()
+            .filter(username_matches(
+                username_redirects::Column::Username,
+                &body.0,
+            ))
+            .filter(username_redirects::Column::CreatedAt.gt(redirect_cutoff()))
+            .find_also_related(Users)
+            .one(db)
+            .await
+            .context("Failed to query username redirect from database")?
+            .and_then(|(_, user_model)| user_model)
+            .ok_or_else(|| anyhow!("User not found"))?,
     };
 
+    let user = user_info(user_model);
+
     let response = GetUserResponse(user);
     Ok(Json(response).into_response())
 }
 
-async fn username_taken(username: &str, db: &'static DatabaseConnection) -> Result<bool, AppError> {
-    let user: Option<users::Model> = Users::find()
-        .filter(users::Column::Username.eq(username))
+fn redirect_cutoff() -> chrono::NaiveDateTime {
+    Utc::now().naive_utc() - Duration::days(USERNAME_REDIRECT_DAYS)
+}
+
+fn validate_profile(username: &str, nickname: &str) -> Result<(String, String), AppError> {
+    let username = validate_username(username).map_err(AppError::validation)?;
+    let nickname = validate_nickname(nickname).map_err(AppError::validation)?;
+    Ok((username, nickname))
+}
+
+fn username_taken_error() -> AppError {
+    AppError::validation(ValidationError::new(Field::Username, Reason::Taken))
+}
+
+fn username_matches(column: impl ColumnTrait, username: &str) -> SimpleExpr {
+    Expr::expr(Func::lower(column.into_expr())).eq(username_key(username))
+}
+
+fn is_username_conflict(err: &DbErr) -> bool {
+    matches!(
+        err.sql_err(),
+        Some(SqlErr::UniqueConstraintViolation(message)) if message.contains(USERNAME_UNIQUE_INDEX)
+    )
+}
+
+async fn username_taken(
+    username: &str,
+    user_uuid: Option<Uuid>,
+    db: &impl ConnectionTrait,
+) -> Result<bool, AppError> {
+    let mut user_query = Users::find().filter(username_matches(users::Column::Username, username));
+    if let Some(user_uuid) = user_uuid {
+        user_query = user_query.filter(users::Column::Uuid.ne(user_uuid));
+    }
+    let user: Option<users::Model> = user_query
         .one(db)
         .await
         .context("Failed to query user from database")?;
+    if user.is_some() {
+        return Ok(true);
+    }
+
+    let mut redirect_query = UsernameRedirects::find()
+        .filter(username_matches(
+            username_redirects::Column::Username,
+            username,
+        ))
+        .filter(username_redirects::Column::CreatedAt.gt(redirect_cutoff()));
+    if let Some(user_uuid) = user_uuid {
+        redirect_query = redirect_query.filter(username_redirects::Column::UserUuid.ne(user_uuid));
+    }
+    let redirect: Option<username_redirects::Model> = redirect_query
+        .one(db)
+        .await
+        .context("Failed to query username redirect from database")?;
 
-    Ok(user.is_some())
+    Ok(redirect.is_some())
 }
 
 pub async fn setup_user(
-    headers: HeaderMap,
+    KratosSession(session): KratosSession,
     Json(body): Json<SetupUserRequest>,
 ) -> Result<Response, AppError> {
-    let email = verify_kratos_cookie(&headers)
-        .await
-        .context("Failed to verify Kratos cookie")?
-        .identity
-        .traits
-        .email;
+    let email = session.identity.traits.email;
     let db = db().await;
 
     let user_model: Option<users::Model> = Users::find()
@@ -150,24 +245,9 @@ pub async fn setup_user(
         return Err(anyhow!("User with this email already exists").into());
     }
 
-    if body.username.len() < 3 || body.username.len() > 20 {
-        return Err(anyhow!("Username must be between 3 and 20 characters").into());
-    }
-    if body.nickname.len() < 3 || body.nickname.len() > 30 {
-        return Err(anyhow!("Nickname must be between 3 and 30 characters").into());
-    }
-    if !body
-        .username
-        .chars()
-        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
-    {
-        return Err(anyhow!(
-            "Username can only contain alphanumeric characterl, underscores, dots, and hyphens"
-        )
-        .into());
-    }
-    if username_taken(&body.username, db).await? {
-        return Err(anyhow!("Username is already taken").into());
+    let (username, nickname) = validate_profile(&body.username, &body.nickname)?;
+    if username_taken(&username, None, db).await? {
+        return Err(username_taken_error());
     }
 
     let email_hash = format!("{:x}", md5::compute(&email));
@@ -175,70 +255,278 @@ pub async fn setup_user(
     let new_user = users::ActiveModel {
         email: Set(email),
         email_hash: Set(email_hash),
-        username: Set(body.username),
-        nickname: Set(body.nickname),
+        username: Set(username),
+        nickname: Set(nickname),
         ..Default::default()
     };
-    new_user
-        .insert(db)
-        .await
-        .context("Failed to insert new user into database")?;
+    match new_user.insert(db).await {
+        Ok(_) => {}
+        Err(e) if is_username_conflict(&e) => return Err(username_taken_error()),
+        Err(e) => {
+            return Err(anyhow!(e)
+                .context("Failed to insert new user into database")
+                .into())
+        }
+    }
 
     let response = SetupUserResponse {};
     Ok(Json(response).into_response())
 }
 
-pub async fn list_users(
-    headers: HeaderMap,
-    Json(body): Json<ListUsersRequest>,
+pub async fn update_profile(
+    AuthUser(user): AuthUser,
+    Json(body): Json<UpdateProfileRequest>,
 ) -> Result<Response, AppError> {
-    let user = verify_jwt(&headers).await?;
     let db = db().await;
 
-    let user_models: Vec<users::Model> = if body.exclude_self {
-        Users::find()
-            .filter(users::Column::Uuid.ne(user.uuid))
-            .all(db)
+    let (username, nickname) = validate_profile(&body.username, &body.nickname)?;
+
+    let txn = db
+        .begin()
+        .await
+        .context("Failed to start database transaction")?;
+
+    if username_key(&username) != username_key(&user.username) {
+        if username_taken(&username, Some(user.uuid), &txn).await? {
+            return Err(username_taken_error());
+        }
+
+        UsernameRedirects::delete_many()
+            .filter(username_matches(
+                username_redirects::Column::Username,
+                &username,
+            ))
+            .exec(&txn)
             .await
-            .context("Failed to query users from database")?
-    } else {
-        Users::find()
-            .all(db)
+            .context("Failed to delete username redirect from database")?;
+
+        let redirect = username_redirects::ActiveModel {
+            username: Set(user.username.clone()),
+            user_uuid: Set(user.uuid),
+            created_at: Set(Utc::now().naive_utc()),
+        };
+        UsernameRedirects::insert(redirect)
+            .on_conflict(
+                OnConflict::column(username_redirects::Column::Username)
+                    .update_columns([
+                        username_redirects::Column::UserUuid,
+                        username_redirects::Column::CreatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&txn)
             .await
-            .context("Failed to query users from database")?
+            .context("Failed to insert username redirect into database")?;
+    }
+
+    let mut user_active: users::ActiveModel = user.into();
+    user_active.username = Set(username);
+    user_active.nickname = Set(nickname);
+    let user = match user_active.update(&txn).await {
+        Ok(user) => user,
+        Err(e) if is_username_conflict(&e) => return Err(username_taken_error()),
+        Err(e) => {
+            return Err(anyhow!(e)
+                .context("Failed to update user in database")
+                .into())
+        }
     };
 
-    let users: Vec<UserInfo> = user_models
-        .into_iter()
-        .map(|u| UserInfo {
-            uuid: u.uuid,
-            email_hash: u.email_hash,
-            username: u.username,
-            nickname: u.nickname,
-        })
-        .collect();
+    txn.commit()
+        .await
+        .context("Failed to commit database transaction")?;
 
-    let response = ListUsersResponse(users);
+    let user = user_info(user);
+    if let Err(e) = publish_user_updated(&user).await {
+        tracing::error!("Failed to publish profile update: {:?}", e);
+    }
+
+    let response = UpdateProfileResponse(user);
+    Ok(Json(response).into_response())
+}
+
+pub async fn set_status(
+    AuthUser(user): AuthUser,
+    Json(body): Json<SetStatusRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let bio = validate_bio(&body.bio).map_err(AppError::validation)?;
+    let status = body
+        .status
+        .map(|status| validate_status(&status, Utc::now().naive_utc()))
+        .transpose()
+        .map_err(AppError::validation)?;
+
+    let mut user_active: users::ActiveModel = user.into();
+    user_active.bio = Set(bio);
+    match status {
+        Some(status) => {
+            user_active.status_emoji = Set(Some(status.emoji));
+            user_active.status_text = Set(Some(status.text));
+            user_active.status_expires_at = Set(status.expires_at);
+        }
+        None => {
+            user_active.status_emoji = Set(None);
+            user_active.status_text = Set(None);
+            user_active.status_expires_at = Set(None);
+        }
+    }
+    let user = user_active
+        .update(db)
+        .await
+        .context("Failed to update user in database")?;
+
+    let user = user_info(user);
+    if let Err(e) = publish_user_updated(&user).await {
+        tracing::error!("Failed to publish status update: {:?}", e);
+    }
+
+    let response = SetStatusResponse(user);
+    Ok(Json(response).into_response())
+}
+
+async fn clear_expired_statuses() -> Result<(), AppError> {
+    let db = db().await;
+
+    let expired: Vec<users::Model> = Users::find()
+        .filter(users::Column::StatusExpiresAt.lte(Utc::now().naive_utc()))
+        .all(db)
+        .await
+        .context("Failed to query expired statuses from database")?;
+
+    for user in expired {
+        let mut user_active: users::ActiveModel = user.into();
+        user_active.status_emoji = Set(None);
+        user_active.status_text = Set(None);
+        user_active.status_expires_at = Set(None);
+        let user = user_active
+            .update(db)
+            .await
+            .context("Failed to update user in database")?;
+
+        if let Err(e) = publish_user_updated(&user_info(user)).await {
+            tracing::error!("Failed to publish cleared status: {:?}", e);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn sweep_statuses() {
+    loop {
+        tokio::time::sleep(STATUS_SWEEP_INTERVAL).await;
+
+        if let Err(e) = clear_expired_statuses().await {
+            tracing::error!("Failed to clear expired statuses: {:?}", e);
+        }
+    }
+}
+
+fn escape_like(query: &str) -> String {
+    query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+pub async fn search_users(
+    AuthUser(user): AuthUser,
+    Json(body): Json<SearchUsersRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let query = body.query.trim().to_string();
+    if query.is_empty() {
+        let response = SearchUsersResponse {
+            users: Vec::new(),
+            next_cursor: None,
+        };
+        return Ok(Json(response).into_response());
+    }
+
+    let limit = body.limit.clamp(1, SEARCH_LIMIT_MAX);
+    let offset = body.cursor.unwrap_or(0);
+    let pattern = format!("{}%", escape_like(&query));
+
+    let blocked_by_user = Query::select()
+        .column(user_blocks::Column::BlockedUuid)
+        .from(UserBlocks)
+        .and_where(user_blocks::Column::BlockerUuid.eq(user.uuid))
+        .to_owned();
+    let blocking_user = Query::select()
+        .column(user_blocks::Column::BlockerUuid)
+        .from(UserBlocks)
+        .and_where(user_blocks::Column::BlockedUuid.eq(user.uuid))
+        .to_owned();
+
+    let mut user_models: Vec<users::Model> = Users::find()
+        .filter(users::Column::Uuid.ne(user.uuid))
+        .filter(users::Column::BannedAt.is_null())
+        .filter(users::Column::DeletedAt.is_null())
+        .filter(users::Column::Discoverable.eq(true))
+        .filter(users::Column::Uuid.not_in_subquery(blocked_by_user))
+        .filter(users::Column::Uuid.not_in_subquery(blocking_user))
+        .filter(Expr::cust_with_values(
+            "(username ILIKE $1 OR nickname ILIKE $1 OR username % $2 OR nickname % $2)",
+            [pattern.clone(), query.clone()],
+        ))
+        .order_by(
+            Expr::cust_with_values(
+                "CASE WHEN username ILIKE $1 OR nickname ILIKE $1 THEN 1 ELSE 0 END \
+                 + GREATEST(similarity(username, $2), similarity(nickname, $2))",
+                [pattern, query],
+            ),
+            Order::Desc,
+        )
+        .order_by_asc(users::Column::Uuid)
+        .offset(offset)
+        .limit(limit + 1)
+        .all(db)
+        .await
+        .context("Failed to search users in database")?;
+
+    let next_cursor = if user_models.len() as u64 > limit {
+        user_models.truncate(limit as usize);
+        Some(offset + limit)
+    } else {
+        None
+    };
+
+    let response = SearchUsersResponse {
+        users: user_models.into_iter().map(user_info).collect(),
+        next_cursor,
+    };
     Ok(Json(response).into_response())
 }
 
 pub async fn chat_users(
-    headers: HeaderMap,
+    AuthUser(user): AuthUser,
     Json(body): Json<ChatUsersRequest>,
 ) -> Result<Response, AppError> {
-    let user = verify_jwt(&headers).await?;
     let db = db().await;
 
-    let chat_members: Vec<chat_members::Model> = ChatMembers::find()
-        .filter(chat_members::Column::ChatUuid.eq(body.0))
+    let chat: chats::Model = Chats::find()
+        .join(JoinType::InnerJoin, chats::Relation::ChatMembers.def())
+        .filter(chats::Column::Uuid.eq(body.0))
+        .filter(chat_members::Column::UserUuid.eq(user.uuid))
+        .one(db)
+        .await
+        .context("Failed to query chat from database")?
+        .ok_or_else(|| anyhow!("User is not a member of this chat"))?;
+
+    let mut chat_members_query =
+        ChatMembers::find().filter(chat_members::Column::ChatUuid.eq(chat.uuid));
+    if chat.kind == sea_orm_active_enums::ChatKind::Broadcast {
+        chat_members_query = chat_members_query
+            .filter(chat_members::Column::Role.ne(sea_orm_active_enums::ChatRole::Member));
+    }
+
+    let chat_members: Vec<chat_members::Model> = chat_members_query
         .all(db)
         .await
         .context("Failed to query chat members from database")?;
 
-    if !chat_members.iter().any(|cm| cm.user_uuid == user.uuid) {
-        return Err(anyhow!("User is not a member of this chat").into());
-    }
-
     let user_uuids: Vec<sea_orm::prelude::Uuid> =
         chat_members.into_iter().map(|cm| cm.user_uuid).collect();
 
@@ -248,16 +536,106 @@ pub async fn chat_users(
         .await
         .context("Failed to query users from database")?;
 
-    let users: Vec<UserInfo> = user_models
-        .into_iter()
-        .map(|u| UserInfo {
-            uuid: u.uuid,
-            email_hash: u.email_hash,
-            username: u.username,
-            nickname: u.nickname,
-        })
-        .collect();
+    let users: Vec<UserInfo> = user_models.into_iter().map(user_info).collect();
+
+    let response = ListUsersResponse(users);
+    Ok(Json(response).into_response())
+}
+
+pub async fn is_blocked(first_uuid: Uuid, second_uuid: Uuid) -> Result<bool, AppError> {
+    let block: Option<user_blocks::Model> = UserBlocks::find()
+        .filter(
+            Condition::any()
+                .add(
+                    user_blocks::Column::BlockerUuid
+                        .eq(first_uuid)
+                        .and(user_blocks::Column::BlockedUuid.eq(second_uuid)),
+                )
+                .add(
+                    user_blocks::Column::BlockerUuid
+                        .eq(second_uuid)
+                        .and(user_blocks::Column::BlockedUuid.eq(first_uuid)),
+                ),
+        )
+        .one(db().await)
+        .await
+        .context("Failed to query user blocks from database")?;
+
+    Ok(block.is_some())
+}
+
+pub async fn list_blocked_users(AuthUser(user): AuthUser) -> Result<Response, AppError> {
+    let db = db().await;
+
+    let user_models: Vec<users::Model> = Users::find()
+        .join(
+            JoinType::InnerJoin,
+            user_blocks::Relation::Users2.def().rev(),
+        )
+        .filter(user_blocks::Column::BlockerUuid.eq(user.uuid))
+        .all(db)
+        .await
+        .context("Failed to query blocked users from database")?;
+
+    let users: Vec<UserInfo> = user_models.into_iter().map(user_info).collect();
 
     let response = ListUsersResponse(users);
     Ok(Json(response).into_response())
 }
+
+pub async fn block_user(
+    AuthUser(user): AuthUser,
+    Json(body): Json<BlockUserRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    if body.0 == user.uuid {
+        return Err(anyhow!("Cannot block yourself").into());
+    }
+
+    let blocked_user: users::Model = Users::find()
+        .filter(users::Column::Uuid.eq(body.0))
+        .one(db)
+        .await
+        .context("Failed to query user from database")?
+        .ok_or_else(|| anyhow!("User not found"))?;
+
+    let new_block = user_blocks::ActiveModel {
+        blocker_uuid: Set(user.uuid),
+        blocked_uuid: Set(blocked_user.uuid),
+        ..Default::default()
+    };
+    UserBlocks::insert(new_block)
+        .on_conflict(
+            OnConflict::columns([
+                user_blocks::Column::BlockerUuid,
+                user_blocks::Column::BlockedUuid,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .do_nothing()
+        .exec(db)
+        .await
+        .context("Failed to insert user block into database")?;
+
+    let response = BlockUserResponse {};
+    Ok(Json(response).into_response())
+}
+
+pub async fn unblock_user(
+    AuthUser(user): AuthUser,
+    Json(body): Json<UnblockUserRequest>,
+) -> Result<Response, AppError> {
+    let db = db().await;
+
+    UserBlocks::delete_many()
+        .filter(user_blocks::Column::BlockerUuid.eq(user.uuid))
+        .filter(user_blocks::Column::BlockedUuid.eq(body.0))
+        .exec(db)
+        .await
+        .context("Failed to delete user block from database")?;
+
+    let response = UnblockUserResponse {};
+    Ok(Json(response).into_response())
+}
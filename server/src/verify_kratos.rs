@@ -1,16 +1,36 @@
-use anyhow::{Context, Result};
-use axum::http::HeaderMap;
+use axum::{
+    extract::FromRequestParts,
+    http::{request::Parts, HeaderMap},
+};
 
-use utils::auth::{server::get_user_from_cookie, KratosUserData};
+use crate::AppError;
+use utils::{
+    auth::{server::get_user_from_cookie, KratosUserData},
+    errors::AuthReason,
+};
 
-pub async fn verify_kratos_cookie(headers: &HeaderMap) -> Result<KratosUserData> {
+pub async fn verify_kratos_cookie(headers: &HeaderMap) -> Result<KratosUserData, AppError> {
     let cookie = headers
         .get("Cookie")
-        .context("No Cookie header found")?
-        .to_str()
-        .context("Failed to convert Cookie header to str")?;
+        .and_then(|h| h.to_str().ok())
+        .ok_or(AppError::Auth(AuthReason::MissingSession))?;
 
     get_user_from_cookie(cookie)
-        .await
-        .context("Failed to verify Kratos cookie")
+        .await?
+        .ok_or(AppError::Auth(AuthReason::InvalidSession))
+}
+
+pub struct KratosSession(pub KratosUserData);
+
+impl<S> FromRequestParts<S> for KratosSession
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        verify_kratos_cookie(&parts.headers)
+            .await
+            .map(KratosSession)
+    }
 }
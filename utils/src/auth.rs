@@ -18,24 +18,32 @@ pub struct Traits {
 #[cfg(feature = "server")]
 pub mod server {
     use anyhow::{Context, Result, bail};
-    use reqwest::Client;
+    use reqwest::{Client, StatusCode};
 
     use super::*;
     use crate::config::{endpoints::auth::URI_WHOAMI, on_auth_base_url};
 
-    pub async fn get_user_from_cookie(cookie: &str) -> Result<KratosUserData> {
+    pub async fn get_user_from_cookie(cookie: &str) -> Result<Option<KratosUserData>> {
         let res = Client::new()
             .get(on_auth_base_url(URI_WHOAMI))
             .header("Cookie", cookie)
             .send()
             .await?;
 
+        if matches!(
+            res.status(),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        ) {
+            return Ok(None);
+        }
         if !res.status().is_success() {
             bail!("Failed to verify session with Kratos: {}", res.status());
         }
 
-        res.json::<KratosUserData>()
+        let user = res
+            .json::<KratosUserData>()
             .await
-            .context("Failed to parse user info from Kratos")
+            .context("Failed to parse user info from Kratos")?;
+        Ok(Some(user))
     }
 }
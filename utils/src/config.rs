@@ -20,9 +20,23 @@ pub fn env_value(key: &str) -> String {
     }
 }
 
+#[cfg(debug_assertions)]
+pub fn optional_env_value(key: &str) -> Option<String> {
+    if let Err(e) = dotenvy::from_filename(".env") {
+        panic!("Failed to read .env: {}", e);
+    }
+
+    std::env::var(key).ok().filter(|value| !value.is_empty())
+}
+
+#[cfg(not(debug_assertions))]
+pub fn optional_env_value(key: &str) -> Option<String> {
+    std::env::var(key).ok().filter(|value| !value.is_empty())
+}
+
 #[cfg(feature = "server")]
 pub mod server {
-    use super::env_value;
+    use super::{env_value, optional_env_value};
 
     pub fn db_url() -> String {
         env_value("DATABASE_URL").trim_end_matches('/').to_string()
@@ -45,6 +59,22 @@ pub mod server {
     pub fn centrifugo_jwt_secret() -> Vec<u8> {
         env_value("CENTRIFUGO_JWT_SECRET").as_bytes().to_vec()
     }
+
+    pub fn jwt_signing_key_file() -> Option<String> {
+        optional_env_value("JWT_SIGNING_KEY_FILE")
+    }
+
+    pub fn jwt_signing_key_id() -> String {
+        env_value("JWT_SIGNING_KEY_ID")
+    }
+
+    pub fn jwt_signing_algorithm() -> String {
+        optional_env_value("JWT_SIGNING_ALGORITHM").unwrap_or_else(|| "EdDSA".to_string())
+    }
+
+    pub fn jwt_verification_keys_file() -> Option<String> {
+        optional_env_value("JWT_VERIFICATION_KEYS_FILE")
+    }
 }
 
 pub mod endpoints {
@@ -77,17 +107,47 @@ pub mod endpoints {
     pub mod jwt {
         pub const IG_GENERATE: &str = "/jwt/generate";
         pub const IG_VERIFY: &str = "/jwt/verify";
+        pub const IP_REFRESH: &str = "/jwt/refresh";
+        pub const IP_REVOKE: &str = "/jwt/revoke";
         pub const IG_GENERATE_CENTRIFUGO: &str = "/jwt/centrifugo";
+        pub const IP_GENERATE_CENTRIFUGO_SUBSCRIPTION: &str = "/jwt/centrifugo/subscription";
+        pub const IG_JWKS: &str = "/.well-known/jwks.json";
+    }
+
+    pub mod access_tokens {
+        pub const IG_LIST: &str = "/tokens/list";
+        pub const IP_CREATE: &str = "/tokens/create";
+        pub const IP_REVOKE: &str = "/tokens/revoke";
+    }
+
+    pub mod avatars {
+        pub const IG_GET: &str = "/avatars/{user_uuid}";
+        pub const IP_UPLOAD: &str = "/avatars/upload";
+        pub const IP_REMOVE: &str = "/avatars/remove";
     }
 
     pub mod chats {
         pub const IG_LIST: &str = "/chats/list";
         pub const IP_GET: &str = "/chats/get";
         pub const IP_VERIFY_PRIVATE: &str = "/chats/verify_private";
+        pub const IP_PREFERENCES: &str = "/chats/preferences";
+    }
+
+    pub mod contacts {
+        pub const IG_LIST: &str = "/contacts/list";
+        pub const IP_SAVE: &str = "/contacts/save";
+        pub const IP_REMOVE: &str = "/contacts/remove";
+    }
+
+    pub mod folders {
+        pub const IG_LIST: &str = "/folders/list";
+        pub const IP_SAVE: &str = "/folders/save";
+        pub const IP_DELETE: &str = "/folders/delete";
     }
 
     pub mod groups {
         pub const IP_NEW: &str = "/groups/new";
+        pub const IP_SLOW_MODE: &str = "/groups/slow_mode";
     }
 
     pub mod messages {
@@ -95,6 +155,17 @@ pub mod endpoints {
         pub const IP_SEND: &str = "/messages/send";
         pub const IP_DELETE: &str = "/messages/delete";
         pub const IP_EDIT: &str = "/messages/edit";
+        pub const IP_REPORT: &str = "/messages/report";
+    }
+
+    pub mod moderation {
+        pub const IG_REPORTS: &str = "/moderation/reports";
+        pub const IP_RESOLVE: &str = "/moderation/resolve";
+    }
+
+    pub mod presence {
+        pub const IP_HEARTBEAT: &str = "/presence/heartbeat";
+        pub const IP_GET: &str = "/presence/get";
     }
 
     pub mod users {
@@ -103,8 +174,17 @@ pub mod endpoints {
         pub const IP_GET: &str = "/users/get";
         pub const IP_NAME: &str = "/users/name";
         pub const IP_SETUP: &str = "/users/setup";
-        pub const IP_LIST: &str = "/users/list";
+        pub const IP_UPDATE: &str = "/users/update";
+        pub const IP_STATUS: &str = "/users/status";
+        pub const IG_PRIVACY: &str = "/users/privacy";
+        pub const IP_PRIVACY_SAVE: &str = "/users/privacy/save";
+        pub const IG_EXPORT: &str = "/users/export";
+        pub const IP_DELETE: &str = "/users/delete";
+        pub const IP_SEARCH: &str = "/users/search";
         pub const IP_CHAT: &str = "/users/chat";
+        pub const IG_BLOCKED: &str = "/users/blocked";
+        pub const IP_BLOCK: &str = "/users/block";
+        pub const IP_UNBLOCK: &str = "/users/unblock";
     }
 }
 
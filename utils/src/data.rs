@@ -6,15 +6,157 @@ use uuid::Uuid;
 pub struct ChatInfo {
     pub uuid: Uuid,
     pub name: String,
-    pub is_group: bool,
+    pub kind: ChatKind,
+    pub role: ChatRole,
+    pub member_count: u64,
+    pub preferences: ChatPreferences,
+    pub counterpart: Option<UserInfo>,
+    pub last_message: Option<MessagePreview>,
+    pub last_activity: NaiveDateTime,
+    pub slow_mode: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatKind {
+    Private,
+    Group,
+    Broadcast,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatRole {
+    Owner,
+    Admin,
+    Member,
+}
+
+impl ChatRole {
+    pub fn is_admin(&self) -> bool {
+        matches!(self, ChatRole::Owner | ChatRole::Admin)
+    }
+}
+
+impl ChatInfo {
+    pub fn can_post(&self) -> bool {
+        self.kind != ChatKind::Broadcast || self.role.is_admin()
+    }
+
+    pub fn set_last_message(&mut self, preview: MessagePreview) {
+        self.last_activity = self.last_activity.max(preview.created_at);
+        self.last_message = Some(preview);
+    }
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ChatPreferences {
+    pub muted_until: Option<NaiveDateTime>,
+    pub pinned: Option<i32>,
+    pub archived: bool,
+}
+
+impl ChatPreferences {
+    pub fn is_muted(&self, now: NaiveDateTime) -> bool {
+        self.muted_until.is_some_and(|until| until > now)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ChatFolder {
+    pub uuid: Uuid,
+    pub name: String,
+    pub include_all: bool,
+    pub include: Vec<Uuid>,
+    pub filters: FolderFilters,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FolderFilters {
+    pub groups_only: bool,
+    pub unread_only: bool,
+    pub exclude_muted: bool,
+}
+
+impl ChatFolder {
+    pub fn contains(&self, chat: &ChatInfo, unread: bool, now: NaiveDateTime) -> bool {
+        (self.include_all || self.include.contains(&chat.uuid))
+            && (!self.filters.groups_only || chat.kind != ChatKind::Private)
+            && (!self.filters.unread_only || unread)
+            && (!self.filters.exclude_muted || !chat.preferences.is_muted(now))
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MessagePreview {
+    pub uuid: Uuid,
+    pub sender_uuid: Uuid,
+    pub sender_nickname: String,
+    pub content: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl MessagePreview {
+    pub const MAX_LENGTH: usize = 100;
+
+    pub fn truncate(content: &str) -> String {
+        match content.char_indices().nth(Self::MAX_LENGTH) {
+            Some((end, _)) => format!("{}…", &content[..end]),
+            None => content.to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct UserInfo {
     pub uuid: Uuid,
-    pub email_hash: String,
     pub username: String,
     pub nickname: String,
+    pub avatar_version: Option<i64>,
+    #[serde(default)]
+    pub deleted: bool,
+    #[serde(default)]
+    pub bio: String,
+    #[serde(default)]
+    pub status: Option<UserStatus>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ContactInfo {
+    pub user: UserInfo,
+    pub nickname: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl ContactInfo {
+    pub fn display_name(&self) -> &str {
+        self.nickname.as_deref().unwrap_or(&self.user.nickname)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct UserStatus {
+    pub emoji: String,
+    pub text: String,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+impl UserStatus {
+    pub fn is_expired(&self, now: NaiveDateTime) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    pub fn label(&self) -> String {
+        format!("{} {}", self.emoji, self.text).trim().to_string()
+    }
+}
+
+impl UserInfo {
+    pub fn active_status(&self, now: NaiveDateTime) -> Option<&UserStatus> {
+        self.status
+            .as_ref()
+            .filter(|status| !status.is_expired(now))
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -35,3 +177,78 @@ pub struct MessageInfoNoReply {
     pub created_at: NaiveDateTime,
     pub edited_at: Option<NaiveDateTime>,
 }
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationAction {
+    Dismiss,
+    DeleteMessage,
+    RemoveFromGroup,
+    BanUser,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReportInfo {
+    pub uuid: Uuid,
+    pub chat_uuid: Uuid,
+    pub reporter_uuid: Uuid,
+    pub message: MessageInfoNoReply,
+    pub reason: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Presence {
+    pub user_uuid: Uuid,
+    pub online: bool,
+    pub last_seen_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivacyAudience {
+    Everyone,
+    SharedGroup,
+    Nobody,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PrivacySettings {
+    pub dm: PrivacyAudience,
+    pub last_seen: PrivacyAudience,
+    pub discoverable: bool,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenScope {
+    ReadMessages,
+    SendMessages,
+    ManageGroups,
+}
+
+impl TokenScope {
+    pub const ALL: [TokenScope; 3] = [
+        TokenScope::ReadMessages,
+        TokenScope::SendMessages,
+        TokenScope::ManageGroups,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TokenScope::ReadMessages => "Read messages",
+            TokenScope::SendMessages => "Send messages",
+            TokenScope::ManageGroups => "Manage groups",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AccessTokenInfo {
+    pub uuid: Uuid,
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    pub created_at: NaiveDateTime,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+}
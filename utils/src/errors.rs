@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::validation::ValidationError;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthReason {
+    MissingToken,
+    InvalidToken,
+    ExpiredToken,
+    SessionRevoked,
+    SessionExpired,
+    MissingSession,
+    InvalidSession,
+    UserNotFound,
+    UserBanned,
+    UserDeleted,
+    InsufficientScope,
+    ChannelForbidden,
+}
+
+impl fmt::Display for AuthReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            AuthReason::MissingToken => "Missing access token",
+            AuthReason::InvalidToken => "Invalid access token",
+            AuthReason::ExpiredToken => "Access token has expired",
+            AuthReason::SessionRevoked => "Session has been revoked",
+            AuthReason::SessionExpired => "Session has expired",
+            AuthReason::MissingSession => "Missing login session",
+            AuthReason::InvalidSession => "Invalid login session",
+            AuthReason::UserNotFound => "User not found",
+            AuthReason::UserBanned => "User is banned",
+            AuthReason::UserDeleted => "User is deleted",
+            AuthReason::InsufficientScope => "Access token lacks the required scope",
+            AuthReason::ChannelForbidden => "Not allowed to subscribe to this channel",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForbiddenReason {
+    UserBlocked,
+    DirectMessagesRestricted,
+    GroupInvitesRestricted,
+    NotModerator,
+}
+
+impl fmt::Display for ForbiddenReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            ForbiddenReason::UserBlocked => "Cannot message this user",
+            ForbiddenReason::DirectMessagesRestricted => {
+                "This user doesn't accept direct messages from you"
+            }
+            ForbiddenReason::GroupInvitesRestricted => {
+                "This user doesn't accept group invites from you"
+            }
+            ForbiddenReason::NotModerator => "Only moderators can do this",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resource {
+    AccessToken,
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Resource::AccessToken => write!(f, "Access token"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "details", rename_all = "snake_case")]
+pub enum ApiError {
+    SlowMode { retry_after: u64 },
+    RateLimited { retry_after: u64 },
+    Validation(ValidationError),
+    Unauthorized { reason: AuthReason },
+    Forbidden { reason: ForbiddenReason },
+    NotFound { resource: Resource },
+}
+
+impl ApiError {
+    pub fn retry_after(&self) -> Option<u64> {
+        match self {
+            ApiError::SlowMode { retry_after } | ApiError::RateLimited { retry_after } => {
+                Some(*retry_after)
+            }
+            ApiError::Validation(_)
+            | ApiError::Unauthorized { .. }
+            | ApiError::Forbidden { .. }
+            | ApiError::NotFound { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::SlowMode { retry_after } => {
+                write!(f, "Slow mode is enabled, retry in {} seconds", retry_after)
+            }
+            ApiError::RateLimited { retry_after } => {
+                write!(f, "Too many messages, retry in {} seconds", retry_after)
+            }
+            ApiError::Validation(e) => write!(f, "{}", e),
+            ApiError::Unauthorized { reason } => write!(f, "Unauthorized: {}", reason),
+            ApiError::Forbidden { reason } => write!(f, "{}", reason),
+            ApiError::NotFound { resource } => write!(f, "{} not found", resource),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<ValidationError> for ApiError {
+    fn from(err: ValidationError) -> Self {
+        ApiError::Validation(err)
+    }
+}
@@ -4,8 +4,10 @@ compile_error!("Features 'server' and 'client' cannot be enabled at the same tim
 pub mod auth;
 pub mod config;
 pub mod data;
+pub mod errors;
 pub mod requests;
 pub mod updates;
+pub mod validation;
 
 pub trait LogError {
     fn log_error(self) -> Self;
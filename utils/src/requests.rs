@@ -2,7 +2,11 @@ use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::data::{ChatInfo, MessageInfo, UserInfo};
+use crate::data::{
+    AccessTokenInfo, ChatFolder, ChatInfo, ChatKind, ChatPreferences, ContactInfo, FolderFilters,
+    MessageInfo, ModerationAction, Presence, PrivacySettings, ReportInfo, TokenScope, UserInfo,
+    UserStatus,
+};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct GenerateJwtResponse {
@@ -13,6 +17,32 @@ pub struct GenerateJwtResponse {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct VerifyJwtResponse(pub bool);
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SessionTokensResponse {
+    pub jwt: String,
+    pub expires_at: NaiveDateTime,
+    pub refresh_token: String,
+    pub refresh_expires_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RefreshJwtRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RevokeSessionRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RevokeSessionResponse {}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GenerateCentrifugoSubscriptionRequest {
+    pub channel: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ListChatsResponse(pub Vec<ChatInfo>);
 
@@ -30,9 +60,19 @@ pub struct VerifyPrivateChatRequest {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct NewChatResponse(pub Uuid);
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SetChatPreferencesRequest {
+    pub chat_uuid: Uuid,
+    pub preferences: ChatPreferences,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SetChatPreferencesResponse {}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct NewGroupRequest {
     pub title: String,
+    pub kind: ChatKind,
     pub members: Vec<Uuid>,
 }
 
@@ -89,8 +129,42 @@ pub struct SetupUserRequest {
 pub struct SetupUserResponse {}
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct ListUsersRequest {
-    pub exclude_self: bool,
+pub struct UpdateProfileRequest {
+    pub username: String,
+    pub nickname: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UpdateProfileResponse(pub UserInfo);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SetStatusRequest {
+    pub bio: String,
+    pub status: Option<UserStatus>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SetStatusResponse(pub UserInfo);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DeleteAccountRequest {
+    pub confirm_username: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DeleteAccountResponse {}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SearchUsersRequest {
+    pub query: String,
+    pub limit: u64,
+    pub cursor: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SearchUsersResponse {
+    pub users: Vec<UserInfo>,
+    pub next_cursor: Option<u64>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -98,3 +172,130 @@ pub struct ListUsersResponse(pub Vec<UserInfo>);
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ChatUsersRequest(pub Uuid);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BlockUserRequest(pub Uuid);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BlockUserResponse {}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UnblockUserRequest(pub Uuid);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UnblockUserResponse {}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReportMessageRequest {
+    pub message_uuid: Uuid,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReportMessageResponse {}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ListReportsResponse(pub Vec<ReportInfo>);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResolveReportRequest {
+    pub report_uuid: Uuid,
+    pub action: ModerationAction,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResolveReportResponse {}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SetSlowModeRequest {
+    pub chat_uuid: Uuid,
+    pub seconds: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SetSlowModeResponse {}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ListFoldersResponse(pub Vec<ChatFolder>);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SaveFolderRequest {
+    pub uuid: Option<Uuid>,
+    pub name: String,
+    pub include_all: bool,
+    pub include: Vec<Uuid>,
+    pub filters: FolderFilters,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SaveFolderResponse(pub ChatFolder);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DeleteFolderRequest(pub Uuid);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DeleteFolderResponse {}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HeartbeatResponse {}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GetPresenceRequest(pub Vec<Uuid>);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GetPresenceResponse(pub Vec<Presence>);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UploadAvatarResponse(pub UserInfo);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RemoveAvatarResponse(pub UserInfo);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GetPrivacyResponse(pub PrivacySettings);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SavePrivacyRequest(pub PrivacySettings);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SavePrivacyResponse(pub PrivacySettings);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ListContactsResponse(pub Vec<ContactInfo>);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SaveContactRequest {
+    pub user_uuid: Uuid,
+    pub nickname: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SaveContactResponse(pub ContactInfo);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RemoveContactRequest(pub Uuid);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RemoveContactResponse {}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ListAccessTokensResponse(pub Vec<AccessTokenInfo>);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CreateAccessTokenRequest {
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CreateAccessTokenResponse {
+    pub token: String,
+    pub info: AccessTokenInfo,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RevokeAccessTokenRequest(pub Uuid);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RevokeAccessTokenResponse {}
@@ -2,7 +2,9 @@ use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::data::{ChatInfo, MessageInfo};
+use crate::data::{
+    ChatFolder, ChatInfo, ChatPreferences, MessageInfo, ModerationAction, Presence, UserInfo,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "kind", content = "payload")]
@@ -11,6 +13,13 @@ pub enum Update {
     DeleteMessage(DeleteMessagePayload),
     UpdateMessage(UpdateMessagePayload),
     NewChat(ChatInfo),
+    ChatRemoved(Uuid),
+    SlowModeChanged(SlowModeChangedPayload),
+    UpdateChatPreferences(UpdateChatPreferencesPayload),
+    ReportResolved(ReportResolvedPayload),
+    UpdateFolders(Vec<ChatFolder>),
+    UserUpdated(UserInfo),
+    PresenceChanged(Presence),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,3 +34,21 @@ pub struct UpdateMessagePayload {
     pub new_content: String,
     pub edited_at: NaiveDateTime,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateChatPreferencesPayload {
+    pub chat_uuid: Uuid,
+    pub preferences: ChatPreferences,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowModeChangedPayload {
+    pub chat_uuid: Uuid,
+    pub slow_mode: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportResolvedPayload {
+    pub report_uuid: Uuid,
+    pub action: ModerationAction,
+}
@@ -0,0 +1,416 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::data::UserStatus;
+
+pub const USERNAME_MIN_LEN: usize = 3;
+pub const USERNAME_MAX_LEN: usize = 20;
+pub const NICKNAME_MIN_LEN: usize = 1;
+pub const NICKNAME_MAX_LEN: usize = 30;
+pub const MESSAGE_MAX_LEN: usize = 4000;
+pub const GROUP_TITLE_MIN_LEN: usize = 1;
+pub const GROUP_TITLE_MAX_LEN: usize = 64;
+pub const TOKEN_NAME_MIN_LEN: usize = 1;
+pub const TOKEN_NAME_MAX_LEN: usize = 64;
+pub const FOLDER_NAME_MIN_LEN: usize = 1;
+pub const FOLDER_NAME_MAX_LEN: usize = 32;
+pub const BIO_MAX_LEN: usize = 300;
+pub const STATUS_EMOJI_MAX_LEN: usize = 4;
+pub const STATUS_TEXT_MAX_LEN: usize = 80;
+pub const REPORT_REASON_MAX_LEN: usize = 1000;
+pub const SLOW_MODE_MAX_SECONDS: u32 = 60 * 60;
+
+const RESERVED_USERNAMES: &[&str] = &[
+    "admin",
+    "administrator",
+    "api",
+    "deleted",
+    "help",
+    "me",
+    "mod",
+    "moderator",
+    "null",
+    "root",
+    "settings",
+    "support",
+    "system",
+    "undefined",
+];
+const RESERVED_USERNAME_PREFIXES: &[&str] = &["deleted-"];
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    Username,
+    Nickname,
+    Message,
+    GroupTitle,
+    TokenName,
+    TokenScopes,
+    TokenExpiry,
+    FolderName,
+    Bio,
+    Status,
+    StatusEmoji,
+    StatusText,
+    StatusExpiry,
+    ReportReason,
+    SlowMode,
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Field::Username => write!(f, "Username"),
+            Field::Nickname => write!(f, "Nickname"),
+            Field::Message => write!(f, "Message"),
+            Field::GroupTitle => write!(f, "Group title"),
+            Field::TokenName => write!(f, "Token name"),
+            Field::TokenScopes => write!(f, "Token scopes"),
+            Field::TokenExpiry => write!(f, "Token expiry"),
+            Field::FolderName => write!(f, "Folder name"),
+            Field::Bio => write!(f, "Bio"),
+            Field::Status => write!(f, "Status"),
+            Field::StatusEmoji => write!(f, "Status emoji"),
+            Field::StatusText => write!(f, "Status text"),
+            Field::StatusExpiry => write!(f, "Status expiry"),
+            Field::ReportReason => write!(f, "Report reason"),
+            Field::SlowMode => write!(f, "Slow mode interval"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum Reason {
+    Empty,
+    TooShort { min: usize },
+    TooLong { max: usize },
+    InvalidCharacters,
+    Reserved,
+    Taken,
+    InPast,
+    TooLarge { max: u64 },
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ValidationError {
+    pub field: Field,
+    #[serde(flatten)]
+    pub reason: Reason,
+}
+
+impl ValidationError {
+    pub fn new(field: Field, reason: Reason) -> Self {
+        Self { field, reason }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let field = self.field;
+        match &self.reason {
+            Reason::Empty => write!(f, "{} cannot be empty", field),
+            Reason::TooShort { min } => {
+                write!(f, "{} must be at least {} characters", field, min)
+            }
+            Reason::TooLong { max } => write!(f, "{} must be at most {} characters", field, max),
+            Reason::InvalidCharacters => match field {
+                Field::Username => write!(
+                    f,
+                    "Username can only contain letters, digits, underscores, dots, and hyphens"
+                ),
+                _ => write!(f, "{} contains invalid characters", field),
+            },
+            Reason::Reserved => write!(f, "{} is reserved", field),
+            Reason::Taken => write!(f, "{} is already taken", field),
+            Reason::InPast => write!(f, "{} must be in the future", field),
+            Reason::TooLarge { max } => write!(f, "{} must be at most {}", field, max),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+pub fn username_key(username: &str) -> String {
+    username.trim().to_ascii_lowercase()
+}
+
+pub fn validate_username(username: &str) -> Result<String, ValidationError> {
+    let error = |reason| Err(ValidationError::new(Field::Username, reason));
+    let username = username.trim();
+
+    if username.is_empty() {
+        return error(Reason::Empty);
+    }
+    if !username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+    {
+        return error(Reason::InvalidCharacters);
+    }
+    if username.len() < USERNAME_MIN_LEN {
+        return error(Reason::TooShort {
+            min: USERNAME_MIN_LEN,
+        });
+    }
+    if username.len() > USERNAME_MAX_LEN {
+        return error(Reason::TooLong {
+            max: USERNAME_MAX_LEN,
+        });
+    }
+
+    let key = username_key(username);
+    if RESERVED_USERNAMES.contains(&key.as_str())
+        || RESERVED_USERNAME_PREFIXES
+            .iter()
+            .any(|prefix| key.starts_with(prefix))
+    {
+        return error(Reason::Reserved);
+    }
+
+    Ok(username.to_string())
+}
+
+fn normalize_text(text: &str) -> String {
+    text.nfc().collect::<String>().trim().to_string()
+}
+
+fn grapheme_count(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+fn validate_line(
+    field: Field,
+    text: &str,
+    min: usize,
+    max: usize,
+) -> Result<String, ValidationError> {
+    let error = |reason| Err(ValidationError::new(field, reason));
+    let text = normalize_text(text);
+
+    if text.is_empty() {
+        return error(Reason::Empty);
+    }
+    if text.chars().any(char::is_control) {
+        return error(Reason::InvalidCharacters);
+    }
+
+    let count = grapheme_count(&text);
+    if count < min {
+        return error(Reason::TooShort { min });
+    }
+    if count > max {
+        return error(Reason::TooLong { max });
+    }
+
+    Ok(text)
+}
+
+pub fn validate_nickname(nickname: &str) -> Result<String, ValidationError> {
+    validate_line(
+        Field::Nickname,
+        nickname,
+        NICKNAME_MIN_LEN,
+        NICKNAME_MAX_LEN,
+    )
+}
+
+pub fn validate_group_title(title: &str) -> Result<String, ValidationError> {
+    validate_line(
+        Field::GroupTitle,
+        title,
+        GROUP_TITLE_MIN_LEN,
+        GROUP_TITLE_MAX_LEN,
+    )
+}
+
+pub fn validate_token_name(name: &str) -> Result<String, ValidationError> {
+    validate_line(
+        Field::TokenName,
+        name,
+        TOKEN_NAME_MIN_LEN,
+        TOKEN_NAME_MAX_LEN,
+    )
+}
+
+pub fn validate_folder_name(name: &str) -> Result<String, ValidationError> {
+    validate_line(
+        Field::FolderName,
+        name,
+        FOLDER_NAME_MIN_LEN,
+        FOLDER_NAME_MAX_LEN,
+    )
+}
+
+fn validate_text(field: Field, text: &str, max: usize) -> Result<String, ValidationError> {
+    let error = |reason| Err(ValidationError::new(field, reason));
+    let text: String = text.nfc().collect();
+
+    if text.trim().is_empty() {
+        return error(Reason::Empty);
+    }
+    if text
+        .chars()
+        .any(|c| c.is_control() && c != '\n' && c != '\t')
+    {
+        return error(Reason::InvalidCharacters);
+    }
+    if grapheme_count(&text) > max {
+        return error(Reason::TooLong { max });
+    }
+
+    Ok(text)
+}
+
+pub fn validate_message(content: &str) -> Result<String, ValidationError> {
+    validate_text(Field::Message, content, MESSAGE_MAX_LEN)
+}
+
+pub fn validate_report_reason(reason: &str) -> Result<String, ValidationError> {
+    validate_text(Field::ReportReason, reason, REPORT_REASON_MAX_LEN)
+}
+
+pub fn validate_bio(bio: &str) -> Result<String, ValidationError> {
+    if normalize_text(bio).is_empty() {
+        return Ok(String::new());
+    }
+
+    validate_text(Field::Bio, bio, BIO_MAX_LEN)
+}
+
+pub fn validate_slow_mode(seconds: Option<u32>) -> Result<Option<u32>, ValidationError> {
+    match seconds.filter(|seconds| *seconds > 0) {
+        Some(seconds) if seconds > SLOW_MODE_MAX_SECONDS => Err(ValidationError::new(
+            Field::SlowMode,
+            Reason::TooLarge {
+                max: u64::from(SLOW_MODE_MAX_SECONDS),
+            },
+        )),
+        seconds => Ok(seconds),
+    }
+}
+
+pub fn validate_expiry(
+    field: Field,
+    expires_at: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+) -> Result<(), ValidationError> {
+    if expires_at.is_some_and(|expires_at| expires_at <= now) {
+        return Err(ValidationError::new(field, Reason::InPast));
+    }
+
+    Ok(())
+}
+
+pub fn validate_status(
+    status: &UserStatus,
+    now: NaiveDateTime,
+) -> Result<UserStatus, ValidationError> {
+    let emoji = normalize_text(&status.emoji);
+    let text = normalize_text(&status.text);
+
+    if emoji.is_empty() && text.is_empty() {
+        return Err(ValidationError::new(Field::Status, Reason::Empty));
+    }
+    if !emoji.is_empty() {
+        validate_line(Field::StatusEmoji, &emoji, 1, STATUS_EMOJI_MAX_LEN)?;
+    }
+    if !text.is_empty() {
+        validate_line(Field::StatusText, &text, 1, STATUS_TEXT_MAX_LEN)?;
+    }
+    validate_expiry(Field::StatusExpiry, status.expires_at, now)?;
+
+    Ok(UserStatus {
+        emoji,
+        text,
+        expires_at: status.expires_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_graphemes_not_chars() {
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        assert_eq!(grapheme_count(family), 1);
+        assert_eq!(grapheme_count("e\u{301}"), 1);
+
+        let title = "\u{1F44D}".repeat(GROUP_TITLE_MAX_LEN);
+        assert_eq!(validate_group_title(&title), Ok(title));
+        assert_eq!(
+            validate_group_title(&"\u{1F44D}".repeat(GROUP_TITLE_MAX_LEN + 1)),
+            Err(ValidationError::new(
+                Field::GroupTitle,
+                Reason::TooLong {
+                    max: GROUP_TITLE_MAX_LEN
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn normalizes_to_nfc() {
+        assert_eq!(
+            validate_nickname("Jose\u{301}"),
+            Ok("Jos\u{e9}".to_string())
+        );
+        assert_eq!(validate_message("cafe\u{301}"), Ok("caf\u{e9}".to_string()));
+    }
+
+    #[test]
+    fn trims_lines_but_keeps_text_as_sent() {
+        assert_eq!(validate_nickname("  Alice  "), Ok("Alice".to_string()));
+        assert_eq!(
+            validate_message("    indented\n"),
+            Ok("    indented\n".to_string())
+        );
+        assert_eq!(
+            validate_message(" \n\t "),
+            Err(ValidationError::new(Field::Message, Reason::Empty))
+        );
+    }
+
+    #[test]
+    fn rejects_control_characters() {
+        assert_eq!(
+            validate_nickname("Ali\u{7}ce"),
+            Err(ValidationError::new(
+                Field::Nickname,
+                Reason::InvalidCharacters
+            ))
+        );
+        assert_eq!(
+            validate_nickname("Ali\nce"),
+            Err(ValidationError::new(
+                Field::Nickname,
+                Reason::InvalidCharacters
+            ))
+        );
+        assert_eq!(
+            validate_message("hello\u{0}"),
+            Err(ValidationError::new(
+                Field::Message,
+                Reason::InvalidCharacters
+            ))
+        );
+        assert!(validate_message("line one\n\tline two").is_ok());
+    }
+
+    #[test]
+    fn rejects_reserved_usernames() {
+        let reserved = Err(ValidationError::new(Field::Username, Reason::Reserved));
+        assert_eq!(validate_username("admin"), reserved);
+        assert_eq!(validate_username("Admin"), reserved);
+        assert_eq!(validate_username(" root "), reserved);
+        assert_eq!(validate_username("deleted-1234"), reserved);
+        assert_eq!(validate_username("Deleted-user"), reserved);
+        assert_eq!(validate_username("admins"), Ok("admins".to_string()));
+        assert_eq!(validate_username("deleted_1"), Ok("deleted_1".to_string()));
+    }
+}